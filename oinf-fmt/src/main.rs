@@ -0,0 +1,165 @@
+//! `oinf-fmt`: formatter for OpenInfer DSL sources.
+//!
+//! ```text
+//! oinf-fmt [--check] [--rs] [PATH...]
+//! ```
+//!
+//! Formats `.oinf` files in place; directories are searched recursively.
+//! With `--rs`, the bodies of `graph!` invocations in `.rs` files are
+//! formatted too. Without paths, stdin is formatted to stdout. `--check`
+//! lists the files that would change instead of writing them and exits with
+//! status 1 if there are any.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use openinfer_dsl_syntax::format::{format_dsl, format_rust};
+
+const USAGE: &str = "usage: oinf-fmt [--check] [--rs] [PATH...]";
+
+struct Options {
+    check: bool,
+    rust: bool,
+    paths: Vec<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let mut options = Options {
+        check: false,
+        rust: false,
+        paths: Vec::new(),
+    };
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => options.check = true,
+            "--rs" => options.rust = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("unknown option: {}\n{}", arg, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => options.paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let ok = if options.paths.is_empty() {
+        format_stdin(&options)
+    } else {
+        let mut files = Vec::new();
+        for path in &options.paths {
+            collect_files(path, options.rust, true, &mut files);
+        }
+        let mut ok = true;
+        for file in &files {
+            ok &= format_file(file, &options);
+        }
+        ok
+    };
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn format_stdin(options: &Options) -> bool {
+    let mut source = String::new();
+    if let Err(err) = std::io::stdin().read_to_string(&mut source) {
+        eprintln!("cannot read stdin: {}", err);
+        return false;
+    }
+    let Some(formatted) = format_source("<stdin>", &source, options.rust) else {
+        return false;
+    };
+    if options.check {
+        return formatted == source;
+    }
+    std::io::stdout().write_all(formatted.as_bytes()).is_ok()
+}
+
+/// Format one file; returns false if it is invalid or, with `--check`,
+/// would change.
+fn format_file(path: &Path, options: &Options) -> bool {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("cannot read {}: {}", path.display(), err);
+            return false;
+        }
+    };
+    let is_rust = path.extension().is_some_and(|ext| ext == "rs");
+    let Some(formatted) = format_source(&path.display().to_string(), &source, is_rust) else {
+        return false;
+    };
+    if formatted == source {
+        return true;
+    }
+    if options.check {
+        println!("{}", path.display());
+        return false;
+    }
+    if let Err(err) = std::fs::write(path, formatted) {
+        eprintln!("cannot write {}: {}", path.display(), err);
+        return false;
+    }
+    true
+}
+
+/// Format `source`, reporting problems as `name:line:column: message`.
+/// Returns `None` if the source cannot be formatted; `graph!` bodies that
+/// cannot be formatted are reported and left as is.
+fn format_source(name: &str, source: &str, is_rust: bool) -> Option<String> {
+    let result = if is_rust {
+        format_rust(source).map(|(formatted, notes)| {
+            for note in notes {
+                eprintln!("{}:{}", name, note);
+            }
+            formatted
+        })
+    } else {
+        format_dsl(source)
+    };
+    match result {
+        Ok(formatted) => Some(formatted),
+        Err(err) => {
+            for message in err.messages {
+                eprintln!("{}:{}", name, message);
+            }
+            None
+        }
+    }
+}
+
+/// Collect the files to format under `path`. Explicit `.rs` paths and
+/// directory contents need `--rs` to be picked up.
+fn collect_files(path: &Path, rust: bool, explicit: bool, out: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        if !explicit && (name.starts_with('.') || name == "target") {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(path) else {
+            eprintln!("cannot read directory {}", path.display());
+            return;
+        };
+        let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        entries.sort();
+        for entry in entries {
+            collect_files(&entry, rust, false, out);
+        }
+        return;
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("oinf") => out.push(path.to_path_buf()),
+        Some("rs") if rust => out.push(path.to_path_buf()),
+        _ if explicit => eprintln!("skipping {}: not an .oinf file", path.display()),
+        _ => {}
+    }
+}
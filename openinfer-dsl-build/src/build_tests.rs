@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use crate::{compile_graph, fn_name, CompileError};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "openinfer-dsl-build-{}-{}",
+        name,
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).expect("create scratch dir");
+    dir
+}
+
+#[test]
+fn compiles_dsl_file_into_named_function() {
+    let dir = scratch_dir("ok");
+    let path = dir.join("tiny-model.oinf");
+    std::fs::write(
+        &path,
+        "dynamic { x: f32[B]; }\nblock entry {\n    op relu(x) >> x;\n    return;\n}\n",
+    )
+    .expect("write dsl file");
+
+    let out = compile_graph(&path, &dir).expect("compile graph");
+    assert_eq!(out, dir.join("tiny_model.rs"));
+    let contents = std::fs::read_to_string(&out).expect("read output");
+    assert!(contents.starts_with("// Generated by openinfer-dsl-build from "));
+    let file: syn::File = syn::parse_str(&contents).expect("generated code parses");
+    let syn::Item::Fn(func) = &file.items[0] else {
+        panic!("expected a function");
+    };
+    assert_eq!(func.sig.ident, "tiny_model");
+    assert!(contents.contains(":: openinfer :: Graph :: new ()"));
+    assert!(contents.contains("g . add_block (\"entry\")"));
+}
+
+#[test]
+fn reports_dsl_errors_with_line_and_column() {
+    let dir = scratch_dir("err");
+    let path = dir.join("bad.oinf");
+    std::fs::write(
+        &path,
+        "dynamic { x: f32[B]; }\nblock entry {\n    op ad(x) >> x;\n}\n",
+    )
+    .expect("write dsl file");
+
+    let Err(CompileError::Invalid(messages)) = compile_graph(&path, &dir) else {
+        panic!("expected invalid dsl");
+    };
+    assert_eq!(
+        messages,
+        [format!(
+            "{}:3:8: unknown op: ad; did you mean `add`?",
+            path.display()
+        )]
+    );
+
+    let missing = dir.join("missing.oinf");
+    assert!(matches!(
+        compile_graph(&missing, &dir),
+        Err(CompileError::Io { path, .. }) if path == missing
+    ));
+}
+
+#[test]
+fn derives_function_names_from_file_stems() {
+    assert_eq!(fn_name("Llama-7B").expect("name"), "llama_7b");
+    assert_eq!(fn_name("2layer").expect("name"), "_2layer");
+    assert!(matches!(fn_name("fn"), Err(CompileError::InvalidName(_))));
+}
+
+#[test]
+fn json_graphs_expand_to_the_same_code() {
+    let source = r#"
+        dims { B: 1..=8; D = 64; }
+        fn act(x: f32[B, D]) -> f32[B, D] {
+            assign h: f32[B, D];
+            op relu(x) >> h;
+            return h;
+        }
+        dynamic { x: f32[B, D]; }
+        volatile { scale: f32 @init(0.5); }
+        block entry {
+            assign t: f32[B, D];
+            loop l (i in 0..2) @unroll { op add(x, x) >> t; }
+            call act(t) >> t;
+            return;
+        }
+    "#;
+    let graph: openinfer_dsl_syntax::GraphDsl = syn::parse_str(source).expect("parse graph");
+    let json = openinfer_dsl_syntax::json::to_json(&graph);
+    let imported = openinfer_dsl_syntax::json::from_json(&json).expect("read json");
+
+    let expected = crate::expand(graph).expect("expand dsl");
+    let actual = crate::expand(imported).expect("expand json");
+    assert_eq!(actual.to_string(), expected.to_string());
+
+    // Imported graphs go through the same validation.
+    let invalid = openinfer_dsl_syntax::json::from_json(&json.replace("\"relu\"", "\"relux\""))
+        .expect("read json");
+    let err = crate::expand(invalid).expect_err("unknown op");
+    assert!(err.to_string().contains("relux"));
+}
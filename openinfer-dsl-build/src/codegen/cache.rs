@@ -1,6 +1,6 @@
 use quote::quote;
 
-use crate::types::{CacheAccess, CacheIndexExpr, CacheIndexValue};
+use openinfer_dsl_syntax::types::{CacheAccess, CacheIndexExpr, CacheIndexValue};
 
 pub(crate) fn cache_access_expr(access: &CacheAccess) -> syn::Result<proc_macro2::TokenStream> {
     let base = access.name.to_string();
@@ -0,0 +1,59 @@
+use openinfer_dsl_syntax::types::{Dim, DimDecl, DimValue};
+use openinfer_dsl_syntax::validation::shapes::sym_dim;
+use quote::quote;
+
+/// Emit dims in normalised form: like terms are collected, constants are
+/// folded and divisions that cannot be folded are spelled `floor(a/b)` or
+/// `ceil(a/b)`, e.g. `[2*B*D, S+1, ceil(N/8)]`.
+pub(crate) fn dims_expr(dims: &[Dim]) -> syn::Result<proc_macro2::TokenStream> {
+    let items = dims
+        .iter()
+        .map(|dim| {
+            let s = sym_dim(dim)?.to_string();
+            Ok(quote! { #s.to_string() })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! { vec![#(#items),*] })
+}
+
+/// Emit `g.add_dim(..)` for one entry of the `dims` section. Ranges are
+/// always passed as inclusive bounds.
+pub(crate) fn dim_decl_stmt(decl: &DimDecl) -> syn::Result<proc_macro2::TokenStream> {
+    let name = decl.name.to_string();
+    let value = match &decl.value {
+        DimValue::Free => quote! { ::openinfer::DimValue::Free },
+        DimValue::Fixed(lit) => {
+            let value: usize = lit.base10_parse()?;
+            quote! { ::openinfer::DimValue::Fixed(#value) }
+        }
+        DimValue::Range {
+            start,
+            end,
+            inclusive,
+        } => {
+            let min: usize = start.base10_parse()?;
+            let mut max: usize = end.base10_parse()?;
+            if !inclusive {
+                max -= 1;
+            }
+            quote! { ::openinfer::DimValue::Range { min: #min, max: #max } }
+        }
+    };
+    let constraints = decl
+        .constraints
+        .iter()
+        .map(|constraint| {
+            let dividend = sym_dim(&constraint.dividend)?.to_string();
+            let divisor = sym_dim(&constraint.divisor)?.to_string();
+            Ok(quote! {
+                ::openinfer::DimConstraint::DivisibleBy {
+                    dividend: #dividend.to_string(),
+                    divisor: #divisor.to_string(),
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote! {
+        g.add_dim(#name, #value, vec![#(#constraints),*]);
+    })
+}
@@ -2,9 +2,11 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 
-use crate::types::InitValue;
+use openinfer_dsl_syntax::dtype::check_dtype;
+use openinfer_dsl_syntax::types::InitValue;
 
 pub(crate) fn match_dtype(dtype: &Ident) -> syn::Result<TokenStream> {
+    check_dtype(dtype)?;
     let s = dtype.to_string();
     match s.as_str() {
         "i8" => Ok(quote! { ::openinfer::DType::I8 }),
@@ -23,7 +25,7 @@ pub(crate) fn match_dtype(dtype: &Ident) -> syn::Result<TokenStream> {
         "f8" => Ok(quote! { ::openinfer::DType::F8 }),
         "i4" => Ok(quote! { ::openinfer::DType::I4 }),
         "u4" => Ok(quote! { ::openinfer::DType::U4 }),
-        _ => Err(syn::Error::new(dtype.span(), "unsupported dtype")),
+        _ => unreachable!("dtype {} passed check_dtype", s),
     }
 }
 
@@ -120,13 +122,13 @@ pub(crate) fn init_expr(init: &Option<InitValue>, dtype: &Ident) -> syn::Result<
                     quote! { Some(::openinfer::ScalarValue::Bool(#lit_expr != 0)) }
                 }
                 "i4" => {
-                    if value < -8 || value > 7 {
+                    if !(-8..=7).contains(&value) {
                         return Err(syn::Error::new(dtype.span(), "i4 init out of range"));
                     }
                     quote! { Some(::openinfer::ScalarValue::I4(::openinfer::I4::from_i8(#lit_expr as i8))) }
                 }
                 "u4" => {
-                    if value < 0 || value > 15 {
+                    if !(0..=15).contains(&value) {
                         return Err(syn::Error::new(dtype.span(), "u4 init out of range"));
                     }
                     quote! { Some(::openinfer::ScalarValue::U4(::openinfer::U4::from_u8(#lit_expr as u8))) }
@@ -1,19 +1,41 @@
-use quote::quote;
 use crate::codegen::cache::cache_access_expr;
 use crate::codegen::dims::dims_expr;
 use crate::codegen::memory::match_dtype;
-use crate::types::{Node, RangeValue, VarRef};
-use crate::validation;
+use crate::codegen::ops::op_attrs_expr;
+use openinfer_dsl_syntax::types::{Node, RangeValue, VarRef};
+use openinfer_dsl_syntax::validation::ops::registry;
+use openinfer_dsl_syntax::validation::shapes::sym_dim;
+use quote::quote;
 
-use crate::types::{AssignNode, AwaitNode, BranchNode, DepNode, LoopNode, OpNode, TransferNode, YieldNode};
+use openinfer_dsl_syntax::types::{
+    AssignNode, AwaitNode, BranchNode, DepNode, LoopNode, OpNode, TransferNode, YieldNode,
+};
 
 pub(crate) fn node_stmt(node: &Node, block_name: &str) -> syn::Result<proc_macro2::TokenStream> {
     match node {
+        Node::Loop(loop_node) if loop_node.unroll.is_some() => {
+            let stmts = loop_node
+                .body
+                .iter()
+                .map(|node| node_stmt(node, block_name))
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(quote! { #(#stmts)* })
+        }
+        Node::Call(call) => {
+            let stmts = call
+                .inlined
+                .iter()
+                .map(|node| node_stmt(node, block_name))
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(quote! { #(#stmts)* })
+        }
         Node::Loop(loop_node) => {
             let name = loop_node.name.to_string();
             let index = loop_node.index.to_string();
-            let start = range_value_string(&loop_node.start);
-            let end = range_value_string(&loop_node.end);
+            let start = range_value_string(&loop_node.start)?;
+            let end = range_value_string(&loop_node.end)?;
+            let step = loop_step(loop_node)?;
+            let inclusive = loop_node.inclusive;
             let body_expr = loop_body_expr(&loop_node.body)?;
             Ok(quote! {
                 let loop_body = #body_expr;
@@ -22,6 +44,8 @@ pub(crate) fn node_stmt(node: &Node, block_name: &str) -> syn::Result<proc_macro
                     #index.to_string(),
                     #start.to_string(),
                     #end.to_string(),
+                    #step,
+                    #inclusive,
                     loop_body,
                 );
                 g.add_prebuilt_node(#block_name, loop_node)?;
@@ -93,6 +117,10 @@ pub(crate) fn node_kind_expr(node: &Node) -> syn::Result<proc_macro2::TokenStrea
         }
         Node::Transfer(node) => transfer_node_expr(node),
         Node::Loop(loop_node) => loop_node_expr(loop_node),
+        Node::Call(call) => Err(syn::Error::new(
+            call.name.span(),
+            format!("call {} expands to several nodes and cannot be used here", call.name),
+        )),
         Node::Yield(node) => yield_node_expr(node),
         Node::Await(node) => await_node_expr(node),
         Node::Return => Ok(quote! { ::openinfer::NodeKind::Return }),
@@ -102,7 +130,7 @@ pub(crate) fn node_kind_expr(node: &Node) -> syn::Result<proc_macro2::TokenStrea
 fn assign_node_expr(assign: &AssignNode) -> syn::Result<proc_macro2::TokenStream> {
     let name = assign.name.to_string();
     let dtype = match_dtype(&assign.dtype)?;
-    let dims = dims_expr(&assign.dims);
+    let dims = dims_expr(&assign.dims)?;
     Ok(quote! {
         ::openinfer::NodeKind::Assign {
             name: #name.to_string(),
@@ -113,17 +141,15 @@ fn assign_node_expr(assign: &AssignNode) -> syn::Result<proc_macro2::TokenStream
 }
 
 fn op_node_expr(op: &OpNode) -> syn::Result<proc_macro2::TokenStream> {
-    let op_name = op.name.to_string();
-    let op_kind = quote! {
-        ::openinfer::OpKind::from_name(#op_name)
-            .expect("unknown op name")
-    };
+    let spec = registry::lookup(&op.name)?;
+    let variant = syn::Ident::new(spec.kind, op.name.span());
+    let op_kind = quote! { ::openinfer::OpKind::#variant };
     let inputs = op.inputs.iter().map(|i| {
         let s = var_ref_string(i);
         quote! { #s.to_string() }
     });
     let output = op.output.to_string();
-    let attrs = validation::ops::op_attrs_expr(&op.name, &op.settings)?;
+    let attrs = op_attrs_expr(spec, &op.name, &op.settings)?;
     Ok(quote! {
         ::openinfer::NodeKind::Op {
             op: #op_kind,
@@ -137,8 +163,10 @@ fn op_node_expr(op: &OpNode) -> syn::Result<proc_macro2::TokenStream> {
 fn loop_node_expr(loop_node: &LoopNode) -> syn::Result<proc_macro2::TokenStream> {
     let name = loop_node.name.to_string();
     let index = loop_node.index.to_string();
-    let start = range_value_string(&loop_node.start);
-    let end = range_value_string(&loop_node.end);
+    let start = range_value_string(&loop_node.start)?;
+    let end = range_value_string(&loop_node.end)?;
+    let step = loop_step(loop_node)?;
+    let inclusive = loop_node.inclusive;
     let body_expr = loop_body_expr(&loop_node.body)?;
     Ok(quote! {
         ::openinfer::NodeKind::Loop {
@@ -146,6 +174,8 @@ fn loop_node_expr(loop_node: &LoopNode) -> syn::Result<proc_macro2::TokenStream>
             index: #index.to_string(),
             start: #start.to_string(),
             end: #end.to_string(),
+            step: #step,
+            inclusive: #inclusive,
             body: #body_expr,
         }
     })
@@ -177,13 +207,15 @@ fn await_node_expr(node: &AwaitNode) -> syn::Result<proc_macro2::TokenStream> {
 
 pub(crate) fn loop_body_expr(nodes: &[Node]) -> syn::Result<proc_macro2::TokenStream> {
     let mut stmts = Vec::new();
-    for node in nodes {
+    for node in flatten_unrolled(nodes) {
         let stmt = match node {
             Node::Loop(loop_node) => {
                 let name = loop_node.name.to_string();
                 let index = loop_node.index.to_string();
-                let start = range_value_string(&loop_node.start);
-                let end = range_value_string(&loop_node.end);
+                let start = range_value_string(&loop_node.start)?;
+                let end = range_value_string(&loop_node.end)?;
+                let step = loop_step(loop_node)?;
+                let inclusive = loop_node.inclusive;
                 let body_expr = loop_body_expr(&loop_node.body)?;
                 quote! {
                     let loop_body = #body_expr;
@@ -192,6 +224,8 @@ pub(crate) fn loop_body_expr(nodes: &[Node]) -> syn::Result<proc_macro2::TokenSt
                         #index.to_string(),
                         #start.to_string(),
                         #end.to_string(),
+                        #step,
+                        #inclusive,
                         loop_body,
                     ));
                 }
@@ -257,10 +291,34 @@ fn transfer_node_expr(node: &TransferNode) -> syn::Result<proc_macro2::TokenStre
     })
 }
 
-fn range_value_string(value: &RangeValue) -> String {
-    match value {
+/// Splice the expanded bodies of `@unroll` loops and inlined calls into
+/// their parent.
+fn flatten_unrolled(nodes: &[Node]) -> Vec<&Node> {
+    let mut out = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Loop(loop_node) if loop_node.unroll.is_some() => {
+                out.extend(flatten_unrolled(&loop_node.body));
+            }
+            Node::Call(call) => out.extend(flatten_unrolled(&call.inlined)),
+            _ => out.push(node),
+        }
+    }
+    out
+}
+
+fn range_value_string(value: &RangeValue) -> syn::Result<String> {
+    Ok(match value {
         RangeValue::Ident(ident) => ident.to_string(),
         RangeValue::Lit(lit) => lit.to_string(),
+        RangeValue::Expr(dim) => sym_dim(dim)?.to_string(),
+    })
+}
+
+fn loop_step(loop_node: &LoopNode) -> syn::Result<i64> {
+    match &loop_node.step {
+        Some(step) => step.base10_parse(),
+        None => Ok(1),
     }
 }
 
@@ -268,8 +326,8 @@ fn var_ref_string(var_ref: &VarRef) -> String {
     let mut name = var_ref.name.to_string();
     if !var_ref.indices.is_empty() {
         let items = var_ref.indices.iter().map(|index| match index {
-            crate::types::IndexExpr::Ident(ident) => ident.to_string(),
-            crate::types::IndexExpr::Lit(lit) => lit.to_string(),
+            openinfer_dsl_syntax::types::IndexExpr::Ident(ident) => ident.to_string(),
+            openinfer_dsl_syntax::types::IndexExpr::Lit(lit) => lit.to_string(),
         });
         name.push('[');
         name.push_str(&items.collect::<Vec<_>>().join(","));
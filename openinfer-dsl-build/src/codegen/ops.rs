@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use openinfer_dsl_syntax::types::{OpAttrValue, OpSetting};
+use openinfer_dsl_syntax::validation::ops::registry::OpSpec;
+use openinfer_dsl_syntax::validation::ops::schema::{self, AttrKind, ResolvedSetting};
+
+use crate::codegen::memory::match_dtype;
+
+pub(crate) fn op_attrs_expr(
+    spec: &OpSpec,
+    op: &Ident,
+    settings: &[OpSetting],
+) -> syn::Result<TokenStream> {
+    let mut items = Vec::new();
+    for setting in schema::resolve(spec, op, settings)? {
+        let name_literal = setting.name;
+        let value_expr = attr_value_expr(&setting)?;
+        items.push(quote! {
+            ::openinfer::OpAttr {
+                name: #name_literal.to_string(),
+                value: #value_expr,
+            }
+        });
+    }
+    Ok(quote! {
+        ::openinfer::OpAttrs { items: vec![#(#items),*] }
+    })
+}
+
+fn attr_value_expr(setting: &ResolvedSetting) -> syn::Result<TokenStream> {
+    match (setting.kind, &setting.value) {
+        (AttrKind::DType, OpAttrValue::Var(ident)) => {
+            let dtype = match_dtype(ident)?;
+            return Ok(quote! { ::openinfer::AttrValue::DType(#dtype) });
+        }
+        (AttrKind::DTypeList, OpAttrValue::DTypeList(dtypes)) => {
+            let exprs: Vec<TokenStream> = dtypes
+                .iter()
+                .map(match_dtype)
+                .collect::<syn::Result<Vec<_>>>()?;
+            return Ok(quote! { ::openinfer::AttrValue::DTypeList(vec![#(#exprs),*]) });
+        }
+        _ => {}
+    }
+
+    let value = &setting.value;
+    Ok(match value {
+        OpAttrValue::Float(val) => {
+            if val.is_infinite() {
+                if val.is_sign_negative() {
+                    quote! { ::openinfer::AttrValue::Float(::std::f32::NEG_INFINITY) }
+                } else {
+                    quote! { ::openinfer::AttrValue::Float(::std::f32::INFINITY) }
+                }
+            } else {
+                let lit = proc_macro2::Literal::f32_unsuffixed(*val);
+                quote! { ::openinfer::AttrValue::Float(#lit) }
+            }
+        }
+        OpAttrValue::Double(val) => {
+            if val.is_infinite() {
+                if val.is_sign_negative() {
+                    quote! { ::openinfer::AttrValue::Double(::std::f64::NEG_INFINITY) }
+                } else {
+                    quote! { ::openinfer::AttrValue::Double(::std::f64::INFINITY) }
+                }
+            } else {
+                let lit = proc_macro2::Literal::f64_unsuffixed(*val);
+                quote! { ::openinfer::AttrValue::Double(#lit) }
+            }
+        }
+        OpAttrValue::Int(val) => {
+            let lit = proc_macro2::Literal::i64_unsuffixed(*val);
+            quote! { ::openinfer::AttrValue::Int(#lit) }
+        }
+        OpAttrValue::Bool(val) => {
+            quote! { ::openinfer::AttrValue::Bool(#val) }
+        }
+        OpAttrValue::String(val) => {
+            quote! { ::openinfer::AttrValue::Str(#val.to_string()) }
+        }
+        OpAttrValue::IntList(values) => {
+            quote! { ::openinfer::AttrValue::IntList(vec![#(#values),*]) }
+        }
+        OpAttrValue::Var(ident) => {
+            let s = ident.to_string();
+            quote! { ::openinfer::AttrValue::Var(#s.to_string()) }
+        }
+        OpAttrValue::VarList(_) | OpAttrValue::DTypeList(_) => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("setting {} has no runtime representation", setting.name),
+            ));
+        }
+    })
+}
@@ -0,0 +1,147 @@
+//! Compile OpenInfer DSL files into Rust source from a build script.
+//!
+//! This is the build-time counterpart of `graph_file!`: the same parser,
+//! validation and code generation, run once in `build.rs` instead of on
+//! every macro expansion.
+//!
+//! ## Example
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     openinfer_dsl_build::compile_graph("models/llama.oinf", &out_dir).unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/llama.rs"));
+//! let g = llama()?;
+//! ```
+#![warn(missing_docs)]
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use openinfer_dsl_syntax::GraphDsl;
+
+mod codegen;
+
+pub use crate::codegen::expand;
+
+/// Error of [`compile_graph`].
+#[derive(Debug)]
+pub enum CompileError {
+    /// The DSL file could not be read or the output could not be written.
+    Io {
+        /// File that failed.
+        path: PathBuf,
+        /// Underlying error.
+        error: std::io::Error,
+    },
+    /// The file stem is not usable as a function name.
+    InvalidName(String),
+    /// The DSL is invalid; each message is `path:line:column: message`.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            CompileError::InvalidName(name) => {
+                write!(f, "`{}` is not a valid function name", name)
+            }
+            CompileError::Invalid(messages) => write!(f, "{}", messages.join("\n")),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Compile the DSL file at `path` into `out_dir/<stem>.rs`.
+///
+/// The output holds `pub fn <stem>() -> Result<Graph, Box<dyn Error + Send + Sync>>`
+/// with the code `graph_file!` would expand to; the stem is lowercased and
+/// other characters than letters and digits become `_`. Prints
+/// `cargo:rerun-if-changed` for `path` and returns the written file.
+pub fn compile_graph(
+    path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> Result<PathBuf, CompileError> {
+    let path = path.as_ref();
+    println!("cargo:rerun-if-changed={}", path.display());
+    let source = std::fs::read_to_string(path).map_err(|error| CompileError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = fn_name(&stem)?;
+    let body = compile_source(&path.display().to_string(), &source)?;
+
+    let file = quote! {
+        pub fn #name() -> ::std::result::Result<
+            ::openinfer::Graph,
+            ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>,
+        > {
+            ::std::result::Result::Ok(#body)
+        }
+    };
+    let out_path = out_dir.as_ref().join(format!("{}.rs", name));
+    let contents = format!(
+        "// Generated by openinfer-dsl-build from {}. Do not edit.\n{}\n",
+        path.display(),
+        file
+    );
+    std::fs::write(&out_path, contents).map_err(|error| CompileError::Io {
+        path: out_path.clone(),
+        error,
+    })?;
+    Ok(out_path)
+}
+
+/// Parse, validate and expand `source`, the contents of `path`, into the
+/// block expression [`compile_graph`] wraps in a function.
+pub fn compile_source(path: &str, source: &str) -> Result<TokenStream, CompileError> {
+    let tokens: TokenStream = source.parse().map_err(|err: proc_macro2::LexError| {
+        CompileError::Invalid(vec![locate(path, err.span(), &err.to_string())])
+    })?;
+    syn::parse2::<GraphDsl>(tokens)
+        .and_then(expand)
+        .map_err(|err| {
+            CompileError::Invalid(
+                err.into_iter()
+                    .map(|err| locate(path, err.span(), &err.to_string()))
+                    .collect(),
+            )
+        })
+}
+
+fn fn_name(stem: &str) -> Result<syn::Ident, CompileError> {
+    let mut name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    syn::parse_str(&name).map_err(|_| CompileError::InvalidName(stem.to_string()))
+}
+
+fn locate(path: &str, span: Span, message: &str) -> String {
+    let start = span.start();
+    format!("{}:{}:{}: {}", path, start.line, start.column + 1, message)
+}
+
+#[cfg(test)]
+mod build_tests;
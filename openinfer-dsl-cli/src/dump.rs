@@ -0,0 +1,193 @@
+//! The syntax tree as printed by `openinfer-dsl dump`.
+
+use std::fmt::{self, Display, Formatter};
+
+use openinfer_dsl_syntax::types::{DimValue, GraphDsl, Node, Section, VarDecl};
+
+const INDENT: &str = "  ";
+
+/// One line per section, declaration and node, children indented below
+/// their parent. Shows what parsing produced: `repeat` and `@unroll`
+/// expanded, and each `call` followed by its inlined body.
+pub(crate) struct Tree<'a>(pub(crate) &'a GraphDsl);
+
+impl Display for Tree<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Graph")?;
+        for section in &self.0.sections {
+            write_section(f, section)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_section(f: &mut Formatter<'_>, section: &Section) -> fmt::Result {
+    match section {
+        Section::Dims(dims) => {
+            line(f, 1, "Dims")?;
+            for dim in &dims.dims {
+                let value = match &dim.value {
+                    DimValue::Free => "free".to_string(),
+                    DimValue::Fixed(lit) => lit.to_string(),
+                    DimValue::Range {
+                        start,
+                        end,
+                        inclusive,
+                    } => format!("{}{}{}", start, if *inclusive { "..=" } else { ".." }, end),
+                };
+                let mut text = format!("Dim name={} value={}", dim.name, value);
+                if !dim.constraints.is_empty() {
+                    text += &format!(" where={}", list(&dim.constraints));
+                }
+                line(f, 2, &text)?;
+            }
+            Ok(())
+        }
+        Section::Memory(mem) => {
+            line(f, 1, &format!("Memory kind={}", mem.kind))?;
+            for var in &mem.vars {
+                line(f, 2, &var_decl(var))?;
+            }
+            Ok(())
+        }
+        Section::Func(func) => line(
+            f,
+            1,
+            &format!(
+                "Fn name={} params={} dtype={} dims={}",
+                func.name,
+                list(&func.params),
+                func.ret_dtype,
+                list(&func.ret_dims)
+            ),
+        ),
+        Section::Block(block) => {
+            line(f, 1, &format!("Block name={}", block.name))?;
+            write_nodes(f, &block.nodes, 2)
+        }
+    }
+}
+
+fn var_decl(var: &VarDecl) -> String {
+    let mut text = format!(
+        "Var name={} dtype={} dims={}",
+        var.name,
+        var.dtype,
+        list(&var.dims)
+    );
+    if !var.table_indices.is_empty() {
+        text += &format!(" indices={}", list(&var.table_indices));
+    }
+    if let Some(init) = &var.init {
+        text += &format!(" init={}", init);
+    }
+    if let Some(name) = &var.ref_name {
+        text += &format!(" ref={:?}", name.value());
+    }
+    if let Some(pattern) = &var.pattern {
+        text += &format!(" pattern={:?}", pattern.value());
+    }
+    if var.table {
+        text += " table";
+    }
+    if !var.auto_dim.is_empty() {
+        text += &format!(" auto_dim={}", list(&var.auto_dim));
+    }
+    if !var.fixed.is_empty() {
+        let fixed: Vec<String> = var
+            .fixed
+            .iter()
+            .map(|(index, value)| format!("{}={}", index, value))
+            .collect();
+        text += &format!(" fixed={}", list(&fixed));
+    }
+    text
+}
+
+fn write_nodes(f: &mut Formatter<'_>, nodes: &[Node], depth: usize) -> fmt::Result {
+    for node in nodes {
+        write_node(f, node, depth)?;
+    }
+    Ok(())
+}
+
+fn write_node(f: &mut Formatter<'_>, node: &Node, depth: usize) -> fmt::Result {
+    let text = match node {
+        Node::Assign(assign) => format!(
+            "Assign name={} dtype={} dims={}",
+            assign.name,
+            assign.dtype,
+            list(&assign.dims)
+        ),
+        Node::Op(op) => {
+            let mut text = format!("Op name={} inputs={}", op.name, list(&op.inputs));
+            if !op.settings.is_empty() {
+                text += &format!(" settings={}", list(&op.settings));
+            }
+            text + &format!(" output={}", op.output)
+        }
+        Node::Branch(branch) => match (&branch.cond, &branch.else_block) {
+            (Some(cond), Some(else_block)) => format!(
+                "Branch cond={} then={} else={}",
+                cond, branch.then_block, else_block
+            ),
+            _ => format!("Branch then={}", branch.then_block),
+        },
+        Node::Barrier => "Barrier".to_string(),
+        Node::Dep(dep) => format!("Dep after={} before={}", dep.after, dep.before),
+        Node::CacheRead(read) => format!("CacheRead src={} dst={}", read.src, read.dst),
+        Node::CacheWrite(write) => format!("CacheWrite src={} dst={}", write.src, write.dst),
+        Node::CacheInc(inc) => {
+            format!("CacheIncrement target={} amount={}", inc.target, inc.amount)
+        }
+        Node::CacheDec(dec) => {
+            format!("CacheDecrement target={} amount={}", dec.target, dec.amount)
+        }
+        Node::CacheReset(reset) => format!("CacheReset target={}", reset.target),
+        Node::Transfer(transfer) => {
+            format!("Transfer src={} dst={}", transfer.src, transfer.dst)
+        }
+        Node::Loop(loop_node) => {
+            let mut text = format!(
+                "Loop name={} index={} range={}{}{}",
+                loop_node.name,
+                loop_node.index,
+                loop_node.start,
+                if loop_node.inclusive { "..=" } else { ".." },
+                loop_node.end
+            );
+            if let Some(step) = &loop_node.step {
+                text += &format!(" step={}", step);
+            }
+            if loop_node.unroll.is_some() {
+                text += " unrolled";
+            }
+            line(f, depth, &text)?;
+            return write_nodes(f, &loop_node.body, depth + 1);
+        }
+        Node::Call(call) => {
+            let text = format!(
+                "Call name={} args={} output={}",
+                call.name,
+                list(&call.args),
+                call.output
+            );
+            line(f, depth, &text)?;
+            return write_nodes(f, &call.inlined, depth + 1);
+        }
+        Node::Yield(node) => format!("Yield vars={}", list(&node.vars)),
+        Node::Await(node) => format!("Await vars={}", list(&node.vars)),
+        Node::Return => "Return".to_string(),
+    };
+    line(f, depth, &text)
+}
+
+fn line(f: &mut Formatter<'_>, depth: usize, text: &str) -> fmt::Result {
+    writeln!(f, "{}{}", INDENT.repeat(depth), text)
+}
+
+/// `[a, b]`
+fn list<T: Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+    format!("[{}]", items.join(", "))
+}
@@ -0,0 +1,54 @@
+use openinfer_dsl_syntax::GraphDsl;
+
+use crate::dump::Tree;
+
+#[test]
+fn dumps_the_expanded_syntax_tree() {
+    let graph: GraphDsl = syn::parse_str(
+        r#"
+        dims { B: 1..=8; D = 64; }
+        fn twice(x: f32[B, D]) -> f32[B, D] {
+            assign y: f32[B, D];
+            op add(x, x) >> y;
+            return y;
+        }
+        dynamic { x: f32[B, D]; }
+        persistent { kv(l): f16[D] @table; steps: i64 @init(0); }
+        block entry {
+            loop l (i in 0..2) @unroll { cache.read kv[i] >> x; }
+            op relu(x, alpha=0.5) >> x;
+            call twice(x) >> h;
+            cache.increment steps;
+            branch exit;
+        }
+        block exit { return; }
+        "#,
+    )
+    .expect("parse graph");
+    assert_eq!(
+        Tree(&graph).to_string(),
+        "Graph
+  Dims
+    Dim name=B value=1..=8
+    Dim name=D value=64
+  Fn name=twice params=[x: f32[B, D]] dtype=f32 dims=[B, D]
+  Memory kind=dynamic
+    Var name=x dtype=f32 dims=[B, D]
+  Memory kind=persistent
+    Var name=kv dtype=f16 dims=[D] indices=[l] table
+    Var name=steps dtype=i64 dims=[] init=0
+  Block name=entry
+    Loop name=l index=i range=0..2 unrolled
+      CacheRead src=kv[0] dst=x
+      CacheRead src=kv[1] dst=x
+    Op name=relu inputs=[x] settings=[alpha=0.5] output=x
+    Call name=twice args=[x] output=h
+      Assign name=h dtype=f32 dims=[B, D]
+      Op name=add inputs=[x, x] output=h
+    CacheIncrement target=steps amount=1
+    Branch then=exit
+  Block name=exit
+    Return
+"
+    );
+}
@@ -0,0 +1,170 @@
+//! `openinfer-dsl`: the DSL front end outside of rustc.
+//!
+//! ```text
+//! openinfer-dsl check FILE...          report diagnostics as path:line:column
+//! openinfer-dsl fmt [--check] FILE...  format .oinf files in place
+//! openinfer-dsl dump [--code] FILE     print the syntax tree or the generated code
+//! openinfer-dsl stats FILE...          count blocks, loops, vars and nodes
+//! ```
+//!
+//! Every subcommand exits with status 1 if a file is invalid (or, for
+//! `fmt --check`, not formatted) and 2 on usage errors.
+
+use std::process::ExitCode;
+
+use openinfer_dsl_build::{compile_source, CompileError};
+use openinfer_dsl_syntax::format::format_dsl;
+use openinfer_dsl_syntax::{check_source, GraphDsl};
+
+use crate::dump::Tree;
+use crate::stats::Stats;
+
+mod dump;
+mod stats;
+
+const USAGE: &str = "usage:
+    openinfer-dsl check FILE...
+    openinfer-dsl fmt [--check] FILE...
+    openinfer-dsl dump [--code] FILE
+    openinfer-dsl stats FILE...";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+    let (flags, files): (Vec<&String>, Vec<&String>) =
+        rest.iter().partition(|arg| arg.starts_with('-'));
+    let flag = match (command.as_str(), flags.as_slice()) {
+        ("-h" | "--help", _) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        ("check" | "fmt" | "dump" | "stats", []) => false,
+        ("fmt", [flag]) if *flag == "--check" => true,
+        ("dump", [flag]) if *flag == "--code" => true,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if files.is_empty() || (command == "dump" && files.len() != 1) {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    }
+
+    let mut ok = true;
+    for file in files {
+        let Some(source) = read(file) else {
+            ok = false;
+            continue;
+        };
+        ok &= match command.as_str() {
+            "check" => check(file, &source),
+            "fmt" => fmt(file, &source, flag),
+            "dump" => dump(file, &source, flag),
+            _ => stats(file, &source),
+        };
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn read(path: &str) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(source) => Some(source),
+        Err(err) => {
+            eprintln!("cannot read {}: {}", path, err);
+            None
+        }
+    }
+}
+
+fn report(messages: Vec<String>) -> bool {
+    for message in messages {
+        eprintln!("{}", message);
+    }
+    false
+}
+
+/// Parse, validate and expand, as `graph_file!` does.
+fn check(path: &str, source: &str) -> bool {
+    match compile_source(path, source) {
+        Ok(_) => true,
+        Err(CompileError::Invalid(messages)) => report(messages),
+        Err(err) => report(vec![err.to_string()]),
+    }
+}
+
+fn fmt(path: &str, source: &str, check_only: bool) -> bool {
+    let formatted = match format_dsl(source) {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            return report(
+                err.messages
+                    .into_iter()
+                    .map(|message| format!("{}:{}", path, message))
+                    .collect(),
+            )
+        }
+    };
+    if formatted == source {
+        return true;
+    }
+    if check_only {
+        println!("{}", path);
+        return false;
+    }
+    match std::fs::write(path, formatted) {
+        Ok(()) => true,
+        Err(err) => report(vec![format!("cannot write {}: {}", path, err)]),
+    }
+}
+
+fn dump(path: &str, source: &str, code: bool) -> bool {
+    if code {
+        return match compile_source(path, source) {
+            Ok(tokens) => {
+                println!("{}", tokens);
+                true
+            }
+            Err(CompileError::Invalid(messages)) => report(messages),
+            Err(err) => report(vec![err.to_string()]),
+        };
+    }
+    match parse(path, source) {
+        Some(graph) => {
+            print!("{}", Tree(&graph));
+            true
+        }
+        None => false,
+    }
+}
+
+fn stats(path: &str, source: &str) -> bool {
+    match parse(path, source) {
+        Some(graph) => {
+            println!("{}:\n{}", path, Stats::of(&graph));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Parse a file that passes validation, reporting its errors otherwise.
+fn parse(path: &str, source: &str) -> Option<GraphDsl> {
+    if let Err(messages) = check_source(path, source) {
+        report(messages);
+        return None;
+    }
+    syn::parse_str(source).ok()
+}
+
+#[cfg(test)]
+mod dump_tests;
+#[cfg(test)]
+mod stats_tests;
@@ -0,0 +1,165 @@
+//! Counts reported by `openinfer-dsl stats`.
+
+use std::fmt::{self, Display, Formatter};
+
+use openinfer_dsl_syntax::types::{GraphDsl, MemoryKindToken, Node, Section};
+
+const MEMORY_KINDS: [&str; 4] = ["dynamic", "volatile", "constant", "persistent"];
+
+/// Kind of a [`Node`], the index of its count in [`Stats::nodes`].
+#[derive(Clone, Copy)]
+enum NodeKind {
+    Assign,
+    Op,
+    Branch,
+    Barrier,
+    Dep,
+    CacheRead,
+    CacheWrite,
+    CacheInc,
+    CacheDec,
+    CacheReset,
+    Transfer,
+    Loop,
+    Call,
+    Yield,
+    Await,
+    Return,
+}
+
+impl NodeKind {
+    /// Every kind, in report order.
+    const ALL: [NodeKind; 16] = [
+        NodeKind::Assign,
+        NodeKind::Op,
+        NodeKind::Branch,
+        NodeKind::Barrier,
+        NodeKind::Dep,
+        NodeKind::CacheRead,
+        NodeKind::CacheWrite,
+        NodeKind::CacheInc,
+        NodeKind::CacheDec,
+        NodeKind::CacheReset,
+        NodeKind::Transfer,
+        NodeKind::Loop,
+        NodeKind::Call,
+        NodeKind::Yield,
+        NodeKind::Await,
+        NodeKind::Return,
+    ];
+
+    fn of(node: &Node) -> Self {
+        match node {
+            Node::Assign(_) => NodeKind::Assign,
+            Node::Op(_) => NodeKind::Op,
+            Node::Branch(_) => NodeKind::Branch,
+            Node::Barrier => NodeKind::Barrier,
+            Node::Dep(_) => NodeKind::Dep,
+            Node::CacheRead(_) => NodeKind::CacheRead,
+            Node::CacheWrite(_) => NodeKind::CacheWrite,
+            Node::CacheInc(_) => NodeKind::CacheInc,
+            Node::CacheDec(_) => NodeKind::CacheDec,
+            Node::CacheReset(_) => NodeKind::CacheReset,
+            Node::Transfer(_) => NodeKind::Transfer,
+            Node::Loop(_) => NodeKind::Loop,
+            Node::Call(_) => NodeKind::Call,
+            Node::Yield(_) => NodeKind::Yield,
+            Node::Await(_) => NodeKind::Await,
+            Node::Return => NodeKind::Return,
+        }
+    }
+
+    /// DSL keyword of the kind.
+    fn keyword(self) -> &'static str {
+        match self {
+            NodeKind::Assign => "assign",
+            NodeKind::Op => "op",
+            NodeKind::Branch => "branch",
+            NodeKind::Barrier => "barrier",
+            NodeKind::Dep => "dep",
+            NodeKind::CacheRead => "cache.read",
+            NodeKind::CacheWrite => "cache.write",
+            NodeKind::CacheInc => "cache.increment",
+            NodeKind::CacheDec => "cache.decrement",
+            NodeKind::CacheReset => "cache.reset",
+            NodeKind::Transfer => "transfer",
+            NodeKind::Loop => "loop",
+            NodeKind::Call => "call",
+            NodeKind::Yield => "yield",
+            NodeKind::Await => "await",
+            NodeKind::Return => "return",
+        }
+    }
+}
+
+/// Section and node counts of one graph. Nodes are counted as written:
+/// loop bodies are included, inlined fn bodies are not.
+#[derive(Default)]
+pub(crate) struct Stats {
+    pub(crate) blocks: usize,
+    pub(crate) fns: usize,
+    pub(crate) dims: usize,
+    pub(crate) vars: [usize; 4],
+    pub(crate) nodes: [usize; NodeKind::ALL.len()],
+}
+
+impl Stats {
+    pub(crate) fn of(graph: &GraphDsl) -> Self {
+        let mut stats = Stats::default();
+        for section in &graph.sections {
+            match section {
+                Section::Dims(dims) => stats.dims += dims.dims.len(),
+                Section::Memory(mem) => stats.vars[memory_index(&mem.kind)] += mem.vars.len(),
+                Section::Block(block) => {
+                    stats.blocks += 1;
+                    stats.count_nodes(&block.nodes);
+                }
+                Section::Func(_) => stats.fns += 1,
+            }
+        }
+        stats
+    }
+
+    fn count_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.nodes[NodeKind::of(node) as usize] += 1;
+            if let Node::Loop(loop_node) = node {
+                self.count_nodes(&loop_node.body);
+            }
+        }
+    }
+
+    pub(crate) fn loops(&self) -> usize {
+        self.nodes[NodeKind::Loop as usize]
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "blocks: {}", self.blocks)?;
+        writeln!(f, "fns: {}", self.fns)?;
+        writeln!(f, "dims: {}", self.dims)?;
+        writeln!(f, "loops: {}", self.loops())?;
+        writeln!(f, "vars: {}", self.vars.iter().sum::<usize>())?;
+        for (kind, count) in MEMORY_KINDS.iter().zip(self.vars) {
+            writeln!(f, "    {}: {}", kind, count)?;
+        }
+        writeln!(f, "nodes: {}", self.nodes.iter().sum::<usize>())?;
+        for kind in NodeKind::ALL {
+            let count = self.nodes[kind as usize];
+            if count > 0 {
+                writeln!(f, "    {}: {}", kind.keyword(), count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn memory_index(kind: &MemoryKindToken) -> usize {
+    match kind {
+        MemoryKindToken::Dynamic => 0,
+        MemoryKindToken::Volatile => 1,
+        MemoryKindToken::Constant => 2,
+        MemoryKindToken::Persistent => 3,
+    }
+}
@@ -0,0 +1,41 @@
+use openinfer_dsl_syntax::GraphDsl;
+
+use crate::stats::Stats;
+
+#[test]
+fn counts_sections_vars_and_nodes() {
+    let graph: GraphDsl = syn::parse_str(
+        r#"
+        dims { B: 1..=8; D = 64; }
+        fn twice(x: f32[B, D]) -> f32[B, D] {
+            assign y: f32[B, D];
+            op add(x, x) >> y;
+            return y;
+        }
+        dynamic { x: f32[B, D]; }
+        constant { w: f32[D, D]; b: f32[D]; }
+        persistent { steps: i64; }
+        block entry {
+            assign h: f32[B, D];
+            op matmul(x, w) >> h;
+            loop l (i in 0..2) {
+                op add(h, b) >> h;
+                loop m (j in 0..B) { barrier; }
+            }
+            call twice(h) >> h;
+            cache.increment steps;
+            branch exit;
+        }
+        block exit { return; }
+        "#,
+    )
+    .expect("parse graph");
+    let stats = Stats::of(&graph);
+    assert_eq!(stats.loops(), 2);
+    assert_eq!(
+        stats.to_string(),
+        "blocks: 2\nfns: 1\ndims: 2\nloops: 2\nvars: 4\n    dynamic: 1\n    volatile: 0\n    \
+         constant: 2\n    persistent: 1\nnodes: 10\n    assign: 1\n    op: 2\n    branch: 1\n    \
+         barrier: 1\n    cache.increment: 1\n    loop: 2\n    call: 1\n    return: 1\n"
+    );
+}
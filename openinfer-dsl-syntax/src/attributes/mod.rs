@@ -2,6 +2,8 @@ use syn::parse::{ParseStream, Result};
 use syn::Token;
 
 use crate::kw;
+use crate::parsers::node::peek_word;
+use crate::suggest;
 use crate::types::InitValue;
 
 mod init;
@@ -17,6 +19,8 @@ pub struct ParsedAttrs {
     pub fixed: Vec<(syn::Ident, syn::LitInt)>,
 }
 
+const ATTRIBUTES: &[&str] = &["init", "ref", "pattern", "table", "auto_dim", "fixed"];
+
 pub fn parse_attrs(input: ParseStream) -> Result<ParsedAttrs> {
     let mut init = None;
     let mut ref_name = None;
@@ -86,7 +90,11 @@ pub fn parse_attrs(input: ParseStream) -> Result<ParsedAttrs> {
                 return Err(input.error("@fixed requires at least one entry"));
             }
         } else {
-            return Err(input.error("unsupported attribute"));
+            return Err(input.error(suggest::unsupported(
+                "attribute",
+                peek_word(input).as_deref(),
+                ATTRIBUTES,
+            )));
         }
     }
     Ok(ParsedAttrs {
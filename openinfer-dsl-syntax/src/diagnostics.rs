@@ -0,0 +1,35 @@
+//! Error accumulation so one expansion reports every problem at once.
+
+/// Collects errors from the parser and validation passes, combining them
+/// with `syn::Error::combine` into a single compile error.
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    error: Option<syn::Error>,
+}
+
+impl Diagnostics {
+    pub(crate) fn push(&mut self, err: syn::Error) {
+        match &mut self.error {
+            Some(existing) => existing.combine(err),
+            None => self.error = Some(err),
+        }
+    }
+
+    /// Record the error of `result`, if any, and keep going.
+    pub(crate) fn record<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push(err);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> syn::Result<()> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Graphviz DOT rendering of a graph, for design reviews.
+//!
+//! Blocks are clusters and loop bodies nested clusters. Statements are nodes
+//! inside them, with edges from the variables they read to the variables
+//! they write. Branch and dep edges run between block clusters. Variables
+//! are filled by memory kind: dynamic blue, volatile yellow, constant green,
+//! persistent red and `assign` temporaries white.
+
+use std::fmt::{self, Write};
+
+use crate::printer::{Dims, LoopHeader};
+use crate::types::{GraphDsl, MemoryKindToken, Node, Section};
+
+/// Render `graph` as a DOT digraph.
+pub fn to_dot(graph: &GraphDsl) -> String {
+    let mut dot = Dot::default();
+    dot.graph(graph).expect("writing to a String cannot fail");
+    format!(
+        "digraph openinfer {{\n    compound=true;\n    node [fontname=\"monospace\"];\n{}{}{}}}\n",
+        dot.vars, dot.body, dot.edges
+    )
+}
+
+#[derive(Default)]
+struct Dot {
+    /// Variable nodes, declared before the clusters so none claims them.
+    vars: String,
+    /// Block clusters and the statement nodes inside them.
+    body: String,
+    edges: String,
+    declared: Vec<String>,
+    next_node: usize,
+    next_loop: usize,
+}
+
+impl Dot {
+    fn graph(&mut self, graph: &GraphDsl) -> fmt::Result {
+        for section in &graph.sections {
+            if let Section::Memory(mem) = section {
+                for var in &mem.vars {
+                    let label = format!("{}: {}{}", var.name, var.dtype, Dims(&var.dims));
+                    self.var(&var.name.to_string(), &label, memory_color(&mem.kind))?;
+                }
+            }
+        }
+        for section in &graph.sections {
+            if let Section::Block(block) = section {
+                writeln!(self.body, "    subgraph \"cluster_b_{}\" {{", block.name)?;
+                writeln!(self.body, "        label=\"block {}\";", block.name)?;
+                writeln!(self.body, "        \"b_{}\" [shape=point];", block.name)?;
+                self.nodes(&block.nodes, 2)?;
+                writeln!(self.body, "    }}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn var(&mut self, name: &str, label: &str, color: &str) -> fmt::Result {
+        if self.declared.iter().any(|declared| declared == name) {
+            return Ok(());
+        }
+        self.declared.push(name.to_string());
+        writeln!(
+            self.vars,
+            "    \"v_{}\" [label=\"{}\", shape=ellipse, style=filled, fillcolor=\"{}\"];",
+            name,
+            escape(label),
+            color
+        )
+    }
+
+    fn nodes(&mut self, nodes: &[Node], depth: usize) -> fmt::Result {
+        for node in nodes {
+            self.node(node, depth)?;
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, node: &Node, depth: usize) -> fmt::Result {
+        let indent = "    ".repeat(depth);
+        match node {
+            Node::Assign(assign) => {
+                let label = format!("{}: {}{}", assign.name, assign.dtype, Dims(&assign.dims));
+                self.var(&assign.name.to_string(), &label, "white")?;
+            }
+            Node::Op(op) => {
+                let id = self.statement(&indent, &format!("op {}", op.name), "box")?;
+                for input in &op.inputs {
+                    self.read(&input.name, &id)?;
+                }
+                self.write(&id, &op.output)?;
+            }
+            Node::Branch(branch) => {
+                let id = match &branch.cond {
+                    Some(cond) => {
+                        let id = self.statement(&indent, &format!("branch {}", cond), "diamond")?;
+                        self.read(cond, &id)?;
+                        id
+                    }
+                    None => self.statement(&indent, "branch", "diamond")?,
+                };
+                let then_label = if branch.cond.is_some() { "then" } else { "" };
+                self.block_edge(&id, &branch.then_block, then_label)?;
+                if let Some(else_block) = &branch.else_block {
+                    self.block_edge(&id, else_block, "else")?;
+                }
+            }
+            Node::Barrier => {
+                self.statement(&indent, "barrier", "octagon")?;
+            }
+            Node::Dep(dep) => {
+                writeln!(
+                    self.edges,
+                    "    \"b_{0}\" -> \"b_{1}\" [ltail=\"cluster_b_{0}\", lhead=\"cluster_b_{1}\", \
+                     label=\"dep\", style=dashed];",
+                    dep.after, dep.before
+                )?;
+            }
+            Node::CacheRead(read) => {
+                let id = self.statement(&indent, "cache.read", "box")?;
+                self.read(&read.src.name, &id)?;
+                self.write(&id, &read.dst.name)?;
+            }
+            Node::CacheWrite(write) => {
+                let id = self.statement(&indent, "cache.write", "box")?;
+                self.read(&write.src.name, &id)?;
+                self.write(&id, &write.dst.name)?;
+            }
+            Node::CacheInc(inc) => {
+                let label = format!("cache.increment {}", inc.amount);
+                let id = self.statement(&indent, &label, "box")?;
+                self.write(&id, &inc.target)?;
+            }
+            Node::CacheDec(dec) => {
+                let label = format!("cache.decrement {}", dec.amount);
+                let id = self.statement(&indent, &label, "box")?;
+                self.write(&id, &dec.target)?;
+            }
+            Node::CacheReset(reset) => {
+                let id = self.statement(&indent, "cache.reset", "box")?;
+                self.write(&id, &reset.target.name)?;
+            }
+            Node::Transfer(transfer) => {
+                let id = self.statement(&indent, "transfer", "box")?;
+                self.read(&transfer.src.name, &id)?;
+                self.write(&id, &transfer.dst.name)?;
+            }
+            Node::Loop(loop_node) => {
+                let mut label = LoopHeader(loop_node).to_string();
+                if loop_node.unroll.is_some() {
+                    label.push_str(" @unroll");
+                }
+                writeln!(
+                    self.body,
+                    "{}subgraph \"cluster_l{}\" {{",
+                    indent, self.next_loop
+                )?;
+                self.next_loop += 1;
+                writeln!(self.body, "{}    label=\"{}\";", indent, escape(&label))?;
+                self.nodes(&loop_node.body, depth + 1)?;
+                writeln!(self.body, "{}}}", indent)?;
+            }
+            Node::Call(call) => {
+                let id = self.statement(&indent, &format!("call {}", call.name), "component")?;
+                for arg in &call.args {
+                    self.read(arg, &id)?;
+                }
+                self.write(&id, &call.output)?;
+            }
+            Node::Yield(node) => {
+                let id = self.statement(&indent, "yield", "cds")?;
+                for var in &node.vars {
+                    self.read(var, &id)?;
+                }
+            }
+            Node::Await(node) => {
+                let id = self.statement(&indent, "await", "cds")?;
+                for var in &node.vars {
+                    self.write(&id, var)?;
+                }
+            }
+            Node::Return => {
+                self.statement(&indent, "return", "plaintext")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Declare a statement node in the current cluster and return its id.
+    fn statement(&mut self, indent: &str, label: &str, shape: &str) -> Result<String, fmt::Error> {
+        let id = format!("n{}", self.next_node);
+        self.next_node += 1;
+        writeln!(
+            self.body,
+            "{}\"{}\" [label=\"{}\", shape={}];",
+            indent,
+            id,
+            escape(label),
+            shape
+        )?;
+        Ok(id)
+    }
+
+    fn read(&mut self, var: &impl fmt::Display, id: &str) -> fmt::Result {
+        writeln!(self.edges, "    \"v_{}\" -> \"{}\";", var, id)
+    }
+
+    fn write(&mut self, id: &str, var: &impl fmt::Display) -> fmt::Result {
+        writeln!(self.edges, "    \"{}\" -> \"v_{}\";", id, var)
+    }
+
+    fn block_edge(&mut self, from: &str, block: &impl fmt::Display, label: &str) -> fmt::Result {
+        write!(
+            self.edges,
+            "    \"{}\" -> \"b_{1}\" [lhead=\"cluster_b_{1}\"",
+            from, block
+        )?;
+        if !label.is_empty() {
+            write!(self.edges, ", label=\"{}\"", label)?;
+        }
+        writeln!(self.edges, "];")
+    }
+}
+
+fn memory_color(kind: &MemoryKindToken) -> &'static str {
+    match kind {
+        MemoryKindToken::Dynamic => "#cfe2ff",
+        MemoryKindToken::Volatile => "#fff3cd",
+        MemoryKindToken::Constant => "#d1e7dd",
+        MemoryKindToken::Persistent => "#f8d7da",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,56 @@
+use crate::dot::to_dot;
+use crate::types::GraphDsl;
+
+#[test]
+fn renders_blocks_loops_and_memory_as_dot() {
+    let graph: GraphDsl = syn::parse_str(
+        r#"
+        dynamic { x: f32[B]; }
+        constant { w: f32[B]; }
+        volatile { flag: bool; }
+        block entry {
+            assign t: f32[B];
+            op add(x, w) >> t;
+            loop l (i in 0..N) { cache.read x[i] >> t; }
+            dep after(entry) before(exit);
+            branch flag exit entry;
+        }
+        block exit { return; }
+        "#,
+    )
+    .expect("parse graph");
+    let expected = r##"digraph openinfer {
+    compound=true;
+    node [fontname="monospace"];
+    "v_x" [label="x: f32[B]", shape=ellipse, style=filled, fillcolor="#cfe2ff"];
+    "v_w" [label="w: f32[B]", shape=ellipse, style=filled, fillcolor="#d1e7dd"];
+    "v_flag" [label="flag: bool", shape=ellipse, style=filled, fillcolor="#fff3cd"];
+    "v_t" [label="t: f32[B]", shape=ellipse, style=filled, fillcolor="white"];
+    subgraph "cluster_b_entry" {
+        label="block entry";
+        "b_entry" [shape=point];
+        "n0" [label="op add", shape=box];
+        subgraph "cluster_l0" {
+            label="loop l (i in 0..N)";
+            "n1" [label="cache.read", shape=box];
+        }
+        "n2" [label="branch flag", shape=diamond];
+    }
+    subgraph "cluster_b_exit" {
+        label="block exit";
+        "b_exit" [shape=point];
+        "n3" [label="return", shape=plaintext];
+    }
+    "v_x" -> "n0";
+    "v_w" -> "n0";
+    "n0" -> "v_t";
+    "v_x" -> "n1";
+    "n1" -> "v_t";
+    "b_entry" -> "b_exit" [ltail="cluster_b_entry", lhead="cluster_b_exit", label="dep", style=dashed];
+    "v_flag" -> "n2";
+    "n2" -> "b_exit" [lhead="cluster_b_exit", label="then"];
+    "n2" -> "b_entry" [lhead="cluster_b_entry", label="else"];
+}
+"##;
+    assert_eq!(to_dot(&graph), expected);
+}
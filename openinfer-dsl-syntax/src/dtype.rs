@@ -0,0 +1,24 @@
+//! Element dtypes accepted by the DSL.
+
+use syn::Ident;
+
+use crate::suggest;
+
+/// Every dtype name accepted in declarations and op settings.
+pub const DTYPES: &[&str] = &[
+    "i4", "i8", "i16", "i32", "i64", "u4", "u8", "u16", "u32", "u64", "f8", "bf16", "f16", "f32",
+    "f64", "bool",
+];
+
+/// Check that `dtype` names a supported dtype.
+pub fn check_dtype(dtype: &Ident) -> syn::Result<()> {
+    let s = dtype.to_string();
+    if DTYPES.contains(&s.as_str()) {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            dtype.span(),
+            suggest::unsupported("dtype", Some(&s), DTYPES),
+        ))
+    }
+}
@@ -0,0 +1,707 @@
+//! Formatting of `.oinf` sources and `graph!` bodies in Rust files.
+//!
+//! Formatting works on tokens, so comments, `repeat`, `@unroll` and
+//! templates such as `w{i}` are kept as written. It normalises spacing, line
+//! breaks and indentation: each statement gets its own line, braces open a
+//! body indented by four spaces and top-level items are separated by a blank
+//! line. Blank lines between statements are kept, at most one in a row. The
+//! attributes of memory declarations are put in the printer's order:
+//! `@init @ref @pattern @table @auto_dim @fixed`.
+
+use proc_macro2::{LineColumn, Span, TokenStream, TokenTree};
+
+/// Why a source could not be formatted.
+pub struct FormatError {
+    /// One `line:column: message` per problem.
+    pub messages: Vec<String>,
+}
+
+/// Format a whole `.oinf` source.
+pub fn format_dsl(source: &str) -> Result<String, FormatError> {
+    format_text(source, LineColumn { line: 1, column: 0 })
+}
+
+/// Format the body of every `graph!` invocation in a Rust source.
+///
+/// Returns the new source and the problems of each invocation left as is.
+pub fn format_rust(source: &str) -> Result<(String, Vec<String>), FormatError> {
+    let tokens: TokenStream = source
+        .parse()
+        .map_err(|err: proc_macro2::LexError| FormatError {
+            messages: vec![locate(err.span(), &err.to_string())],
+        })?;
+    let mut bodies = Vec::new();
+    find_graph_bodies(tokens, &mut bodies);
+
+    let line_starts = line_starts(source);
+    let mut output = source.to_string();
+    let mut notes = Vec::new();
+    // Replace from the end so earlier offsets stay valid.
+    for (open, close) in bodies.into_iter().rev() {
+        let open_at = offset(source, &line_starts, open.start());
+        let close_at = offset(source, &line_starts, close.start());
+        let text = &source[open_at + 1..close_at];
+        let start = LineColumn {
+            line: open.start().line,
+            column: open.start().column + 1,
+        };
+        let formatted = match format_text(text, start) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                notes.extend(err.messages.into_iter().rev());
+                continue;
+            }
+        };
+        let line = &source[line_starts[open.start().line - 1]..];
+        let indent: String = line
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let mut replacement = String::new();
+        if !formatted.is_empty() {
+            replacement.push('\n');
+            for line in formatted.lines() {
+                if !line.is_empty() {
+                    replacement.push_str(&indent);
+                    replacement.push_str("    ");
+                    replacement.push_str(line);
+                }
+                replacement.push('\n');
+            }
+            replacement.push_str(&indent);
+        }
+        output.replace_range(open_at + 1..close_at, &replacement);
+    }
+    notes.reverse();
+    Ok((output, notes))
+}
+
+/// Collect the delimiter spans of every `graph!` invocation.
+fn find_graph_bodies(tokens: TokenStream, out: &mut Vec<(Span, Span)>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = token else {
+            continue;
+        };
+        let invoked = i >= 2
+            && matches!(&tokens[i - 2], TokenTree::Ident(ident) if ident == "graph")
+            && matches!(&tokens[i - 1], TokenTree::Punct(punct) if punct.as_char() == '!');
+        if invoked {
+            out.push((group.span_open(), group.span_close()));
+        } else {
+            find_graph_bodies(group.stream(), out);
+        }
+    }
+}
+
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Byte offset of a line/column position; columns count characters.
+fn offset(source: &str, line_starts: &[usize], at: LineColumn) -> usize {
+    let start = line_starts[at.line - 1];
+    source[start..]
+        .char_indices()
+        .nth(at.column)
+        .map_or(source.len(), |(i, _)| start + i)
+}
+
+fn locate(span: Span, message: &str) -> String {
+    let start = span.start();
+    format!("{}:{}: {}", start.line, start.column + 1, message)
+}
+
+/// Format DSL `text` that starts at `start` of its file, which positions in
+/// error messages are relative to.
+fn format_text(text: &str, start: LineColumn) -> Result<String, FormatError> {
+    let mut tokens = lex(text, start)?;
+    templates(&tokens)?;
+    sort_attributes(&mut tokens);
+    let templates = templates(&tokens)?;
+    let mut writer = Writer::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        match token.kind {
+            Kind::BlockComment if tokens.get(i + 1).is_some_and(|next| next.newlines == 0) => {
+                // A comment followed by code on its line is part of that line.
+                writer.code(&tokens, i, &templates)
+            }
+            Kind::LineComment | Kind::BlockComment => writer.comment(token),
+            Kind::Open('{') if !templates[i] => {
+                // `{}`: an empty body stays on one line.
+                if matches!(tokens.get(i + 1), Some(next) if next.kind == Kind::Close('}')) {
+                    writer.word(" {}");
+                    writer.end_body();
+                    i += 1;
+                } else {
+                    writer.open_body(token);
+                }
+            }
+            Kind::Close('}') if !templates[i] => writer.close_body(),
+            _ => writer.code(&tokens, i, &templates),
+        }
+        i += 1;
+    }
+    Ok(writer.finish())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Word,
+    Number,
+    Str,
+    Punct,
+    Open(char),
+    Close(char),
+    LineComment,
+    BlockComment,
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    line: usize,
+    /// 0-based column of the first character.
+    column: usize,
+    /// Line breaks between the previous token and this one.
+    newlines: usize,
+    /// Whether whitespace separates this token from the previous one.
+    spaced: bool,
+}
+
+/// Multi-character punctuation, longest first.
+const PUNCTS: [&str; 7] = ["..=", "..", ">>", "->", "==", "!=", "::"];
+
+fn lex(text: &str, start: LineColumn) -> Result<Vec<Token<'_>>, FormatError> {
+    let error = |line: usize, column: usize, message: &str| FormatError {
+        messages: vec![format!("{}:{}: {}", line, column + 1, message)],
+    };
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (start.line, start.column);
+    let (mut newlines, mut spaced) = (0, false);
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            if c == '\n' {
+                line += 1;
+                column = 0;
+                newlines += 1;
+            } else {
+                column += 1;
+            }
+            spaced = true;
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let (kind, len) = if rest.starts_with("//") {
+            (Kind::LineComment, rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            let len = block_comment_len(rest)
+                .ok_or_else(|| error(line, column, "unterminated block comment"))?;
+            (Kind::BlockComment, len)
+        } else if c == '"' {
+            let len = string_len(rest)
+                .ok_or_else(|| error(line, column, "unterminated string literal"))?;
+            (Kind::Str, len)
+        } else if c.is_ascii_digit() {
+            (Kind::Number, number_len(rest))
+        } else if c == '_' || c.is_alphabetic() {
+            let len = rest
+                .find(|c: char| !(c == '_' || c.is_alphanumeric()))
+                .unwrap_or(rest.len());
+            (Kind::Word, len)
+        } else if let Some(punct) = PUNCTS.iter().find(|punct| rest.starts_with(*punct)) {
+            (Kind::Punct, punct.len())
+        } else {
+            let kind = match c {
+                '(' | '[' | '{' => Kind::Open(c),
+                ')' | ']' | '}' => Kind::Close(c),
+                _ if c.is_ascii_punctuation() => Kind::Punct,
+                _ => {
+                    return Err(error(
+                        line,
+                        column,
+                        &format!("unexpected character `{}`", c),
+                    ))
+                }
+            };
+            (kind, c.len_utf8())
+        };
+        let token_text = &rest[..len];
+        tokens.push(Token {
+            kind,
+            text: token_text.trim_end(),
+            line,
+            column,
+            newlines,
+            spaced,
+        });
+        for c in token_text.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 0;
+            } else {
+                column += 1;
+            }
+        }
+        rest = &rest[len..];
+        newlines = 0;
+        spaced = false;
+    }
+    Ok(tokens)
+}
+
+/// Length of a possibly nested `/* */` comment at the start of `text`.
+fn block_comment_len(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < text.len() {
+        if text[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if text[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += text[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// Length of the string literal at the start of `text`, quotes included.
+fn string_len(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Length of the number at the start of `text`: `2`, `0.5`, `1.`, `1e-5`
+/// or `8u32`, but not the `..` of a range such as `0..N`.
+fn number_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let digits = |mut i: usize| {
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_') {
+            i += 1;
+        }
+        i
+    };
+    let mut i = digits(0);
+    if bytes.get(i) == Some(&b'.') && bytes.get(i + 1) != Some(&b'.') {
+        i = digits(i + 1);
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(i + 1), Some(b'+' | b'-')));
+        if bytes.get(i + 1 + sign).is_some_and(u8::is_ascii_digit) {
+            i = digits(i + 1 + sign);
+        }
+    }
+    while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+        i += 1;
+    }
+    i
+}
+
+/// Check that delimiters balance and mark the braces of templates such as
+/// `w{i}`, a brace directly after a word around a single expression.
+fn templates(tokens: &[Token]) -> Result<Vec<bool>, FormatError> {
+    let mut templates = vec![false; tokens.len()];
+    let mut open: Vec<usize> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            Kind::Open(_) => open.push(i),
+            Kind::Close(close) => {
+                let Some(start) = open.pop() else {
+                    return Err(token_error(token, &format!("unexpected `{}`", close)));
+                };
+                let expected = match tokens[start].kind {
+                    Kind::Open('(') => ')',
+                    Kind::Open('[') => ']',
+                    _ => '}',
+                };
+                if close != expected {
+                    return Err(token_error(
+                        token,
+                        &format!("expected `{}`, found `{}`", expected, close),
+                    ));
+                }
+                let inner = &tokens[start + 1..i];
+                let is_template = close == '}'
+                    && start > 0
+                    && tokens[start - 1].kind == Kind::Word
+                    && !tokens[start].spaced
+                    && !inner.is_empty()
+                    && inner.iter().all(|token| {
+                        token.newlines == 0
+                            && (matches!(token.kind, Kind::Word | Kind::Number)
+                                || matches!(token.text, "+" | "-" | "*" | "/"))
+                    });
+                if is_template {
+                    templates[start] = true;
+                    templates[i] = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    match open.pop() {
+        Some(start) => {
+            let Kind::Open(delimiter) = tokens[start].kind else {
+                unreachable!("only open delimiters are pushed");
+            };
+            Err(token_error(
+                &tokens[start],
+                &format!("unclosed `{}`", delimiter),
+            ))
+        }
+        None => Ok(templates),
+    }
+}
+
+/// Keywords of the memory sections, whose declarations carry attributes.
+const MEMORY_KINDS: [&str; 4] = ["dynamic", "volatile", "constant", "persistent"];
+
+/// Declaration attributes in the order the printer writes them.
+const ATTRIBUTE_ORDER: [&str; 6] = ["init", "ref", "pattern", "table", "auto_dim", "fixed"];
+
+/// Put the `@` attributes of every memory-section declaration in
+/// [`ATTRIBUTE_ORDER`]. Delimiters must balance.
+fn sort_attributes(tokens: &mut [Token]) {
+    let mut depth = 0;
+    let mut in_memory = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i].kind {
+            Kind::Open('{') => {
+                if depth == 0 && i > 0 && MEMORY_KINDS.contains(&tokens[i - 1].text) {
+                    in_memory = true;
+                }
+                depth += 1;
+            }
+            Kind::Close('}') => {
+                depth -= 1;
+                in_memory &= depth > 0;
+            }
+            Kind::Punct if in_memory && tokens[i].text == "@" => {
+                i = sort_attribute_run(tokens, i);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Sort the attributes that follow each other from `start`, such as
+/// `@table @ref("w")`, and return the index after the last one. A run with
+/// an unknown attribute is left as written.
+fn sort_attribute_run(tokens: &mut [Token], start: usize) -> usize {
+    let mut attributes = Vec::new();
+    let mut end = start;
+    while end + 1 < tokens.len() && tokens[end].text == "@" && tokens[end + 1].kind == Kind::Word {
+        let rank = ATTRIBUTE_ORDER
+            .iter()
+            .position(|name| *name == tokens[end + 1].text);
+        let from = end;
+        end += 2;
+        if tokens
+            .get(end)
+            .is_some_and(|token| token.kind == Kind::Open('('))
+        {
+            let mut nesting = 0;
+            while end < tokens.len() {
+                match tokens[end].kind {
+                    Kind::Open(_) => nesting += 1,
+                    Kind::Close(_) => nesting -= 1,
+                    _ => {}
+                }
+                end += 1;
+                if nesting == 0 {
+                    break;
+                }
+            }
+        }
+        attributes.push((rank, from, end));
+    }
+    if attributes.iter().all(|(rank, _, _)| rank.is_some()) {
+        attributes.sort_by_key(|(rank, _, _)| *rank);
+        let sorted: Vec<Token> = attributes
+            .iter()
+            .flat_map(|(_, from, to)| tokens[*from..*to].iter().copied())
+            .collect();
+        tokens[start..end].copy_from_slice(&sorted);
+    }
+    end.max(start + 1)
+}
+
+fn token_error(token: &Token, message: &str) -> FormatError {
+    FormatError {
+        messages: vec![format!("{}:{}: {}", token.line, token.column + 1, message)],
+    }
+}
+
+/// Line break owed before the next token.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Break {
+    None,
+    Line,
+    Blank,
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    /// Brace depth, which sets the indentation.
+    depth: usize,
+    /// Parenthesis and bracket depth within the current statement.
+    nesting: usize,
+    /// Whether the current line has any text.
+    line_open: bool,
+    pending: Option<Break>,
+    /// Whether nothing was written since the last `{`.
+    body_start: bool,
+}
+
+impl Writer {
+    /// Start a line for a token that had `newlines` line breaks before it in
+    /// the source, if one is owed.
+    fn flush(&mut self, newlines: usize) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        if pending == Break::None || self.out.is_empty() {
+            return;
+        }
+        let blank = pending == Break::Blank || (newlines > 1 && !self.body_start);
+        self.out.push('\n');
+        if blank {
+            self.out.push('\n');
+        }
+        self.line_open = false;
+    }
+
+    fn owe(&mut self, owed: Break) {
+        if self.pending.is_none_or(|pending| pending < owed) {
+            self.pending = Some(owed);
+        }
+    }
+
+    fn indent(&mut self) {
+        if !self.line_open {
+            self.out.push_str(&"    ".repeat(self.depth));
+            self.line_open = true;
+        }
+    }
+
+    fn word(&mut self, text: &str) {
+        self.indent();
+        self.out.push_str(text);
+        self.body_start = false;
+    }
+
+    fn comment(&mut self, token: &Token) {
+        let trailing = token.newlines == 0 && self.line_open;
+        if trailing {
+            self.out.push(' ');
+        } else {
+            self.owe(Break::Line);
+            self.flush(token.newlines);
+            self.indent();
+        }
+        // Continuation lines of a block comment keep their indentation
+        // relative to its first line.
+        for (i, line) in token.text.lines().enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+                let strip = line
+                    .chars()
+                    .take(token.column)
+                    .take_while(|c| c.is_whitespace())
+                    .count();
+                let line = &line[line
+                    .char_indices()
+                    .nth(strip)
+                    .map_or(line.len(), |(i, _)| i)..];
+                if !line.is_empty() {
+                    self.out.push_str(&"    ".repeat(self.depth));
+                }
+                self.out.push_str(line);
+            } else {
+                self.out.push_str(line);
+            }
+        }
+        self.body_start = false;
+        if token.kind == Kind::LineComment || !trailing {
+            self.owe(Break::Line);
+        }
+    }
+
+    fn open_body(&mut self, token: &Token) {
+        self.flush(token.newlines);
+        if self.line_open {
+            self.out.push(' ');
+        }
+        self.word("{");
+        self.depth += 1;
+        self.nesting = 0;
+        self.body_start = true;
+        self.pending = Some(Break::Line);
+    }
+
+    fn close_body(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        if !self.body_start || self.line_open {
+            self.pending = Some(Break::Line);
+            self.flush(0);
+        }
+        self.word("}");
+        self.end_body();
+    }
+
+    /// After a closing brace: top-level items are separated by a blank line.
+    fn end_body(&mut self) {
+        self.nesting = 0;
+        self.pending = Some(if self.depth == 0 {
+            Break::Blank
+        } else {
+            Break::Line
+        });
+    }
+
+    fn code(&mut self, tokens: &[Token], i: usize, templates: &[bool]) {
+        let token = &tokens[i];
+        if self.pending.is_some() {
+            self.flush(token.newlines);
+        }
+        if self.line_open && self.spaced(tokens, i, templates) {
+            self.out.push(' ');
+        }
+        self.word(token.text);
+        match token.kind {
+            Kind::Open(_) => self.nesting += 1,
+            Kind::Close(_) => self.nesting = self.nesting.saturating_sub(1),
+            Kind::Punct if token.text == ";" && self.nesting == 0 => self.owe(Break::Line),
+            _ => {}
+        }
+    }
+
+    /// Whether a space goes between `tokens[i]` and the token before it.
+    fn spaced(&self, tokens: &[Token], i: usize, templates: &[bool]) -> bool {
+        let next = &tokens[i];
+        if is_comment(next) || (i > 0 && is_comment(&tokens[i - 1])) {
+            return !matches!(tokens[i - 1].kind, Kind::Open(_));
+        }
+        let Some(prev) = tokens[..i].iter().rev().find(|token| !is_comment(token)) else {
+            return false;
+        };
+        if templates[i] || (i > 0 && templates[i - 1] && !is_close(prev)) {
+            // Inside `w{i}`, or right after its opening brace.
+            return false;
+        }
+        if i > 0 && templates[i - 1] && !next.spaced {
+            // A suffix glued to a template, as in `w{i}_scale`.
+            return false;
+        }
+        if matches!(next.kind, Kind::Close(_))
+            || matches!(next.text, "," | ";" | ":" | ".")
+            || matches!(prev.kind, Kind::Open(_))
+            || matches!(prev.text, "@" | "." | ".." | "..=" | "-")
+        {
+            return false;
+        }
+        if matches!(next.text, ".." | "..=") {
+            return prev.text == ",";
+        }
+        if is_compact(tokens, i) {
+            return false;
+        }
+        if let Some(before) = tokens[..i]
+            .iter()
+            .rposition(|token| std::ptr::eq(token, prev))
+        {
+            if is_compact(tokens, before) {
+                return false;
+            }
+        }
+        if next.text == "=" || prev.text == "=" {
+            return self.nesting == 0;
+        }
+        match next.kind {
+            // `op add(`, `@init(`, `f32[`, `x[i]`; but `loop l (` and `in (`.
+            Kind::Open('(') | Kind::Open('[') => match prev.kind {
+                Kind::Word => {
+                    is_keyword(prev.text)
+                        || (next.kind == Kind::Open('(') && is_loop_name(tokens, i))
+                }
+                Kind::Close(_) => next.kind == Kind::Open('('),
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.line_open || !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out
+    }
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(token.kind, Kind::LineComment | Kind::BlockComment)
+}
+
+fn is_close(token: &Token) -> bool {
+    matches!(token.kind, Kind::Close(_))
+}
+
+/// Words after which `(` or `-` starts a new operand instead of continuing
+/// a name.
+fn is_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "in" | "step" | "where" | "return" | "yield" | "await" | "increment" | "decrement"
+    )
+}
+
+/// Whether the `(` at `i` follows the name in `loop name (`.
+fn is_loop_name(tokens: &[Token], i: usize) -> bool {
+    i >= 2 && tokens[i - 2].kind == Kind::Word && tokens[i - 2].text == "loop"
+}
+
+/// Whether the token at `i` is written without spaces around it: the
+/// arithmetic of dimension expressions (`B*D`, `(S-1)/2`) and a unary minus,
+/// which is only compact on its right (`step -1`, `[-1, 0]`).
+fn is_compact(tokens: &[Token], i: usize) -> bool {
+    match tokens[i].text {
+        "+" | "*" | "/" => true,
+        "-" => {
+            let prev = tokens[..i].iter().rev().find(|token| !is_comment(token));
+            match prev {
+                Some(prev) => match prev.kind {
+                    Kind::Word => !is_keyword(prev.text),
+                    Kind::Number | Kind::Close(_) => true,
+                    _ => false,
+                },
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
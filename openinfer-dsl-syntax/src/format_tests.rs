@@ -0,0 +1,107 @@
+use crate::format::{format_dsl, format_rust};
+
+fn formatted(source: &str) -> String {
+    match format_dsl(source) {
+        Ok(formatted) => formatted,
+        Err(err) => panic!("invalid: {:?}", err.messages),
+    }
+}
+
+/// Format `source` and check that formatting the result changes nothing.
+fn assert_formats(source: &str, expected: &str) {
+    assert_eq!(formatted(source), expected);
+    assert_eq!(formatted(expected), expected);
+}
+
+#[test]
+fn formats_dsl_sources() {
+    let source =
+        "dims{B:1..=8;D=64;H where D%H==0;}dynamic{x:f32[B,D];}\nconstant { w : f32 [ D*2 ] @pattern(\"w\")   @ref(\"w\") ; }\n\
+                  block entry{op add(x,w)>>x;loop l(i in 0..N step -1){op relu(x,alpha=0.1,axes=[-1,0])>>x;}\
+                  cache.read state[ i , ..j ]>>x;return;}";
+    let expected = "dims {\n    B: 1..=8;\n    D = 64;\n    H where D % H == 0;\n}\n\n\
+                    dynamic {\n    x: f32[B, D];\n}\n\n\
+                    constant {\n    w: f32[D*2] @ref(\"w\") @pattern(\"w\");\n}\n\n\
+                    block entry {\n    op add(x, w) >> x;\n    loop l (i in 0..N step -1) {\n        \
+                    op relu(x, alpha=0.1, axes=[-1, 0]) >> x;\n    }\n    \
+                    cache.read state[i, ..j] >> x;\n    return;\n}\n";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn keeps_comments() {
+    let source = "// weights\n\n\n// of the model\ndynamic { /* x */ x: f32[B]; // input\n\
+                  /* multi\n     line */\n  y: f32[B];}\n";
+    let expected =
+        "// weights\n\n// of the model\ndynamic {\n    /* x */ x: f32[B]; // input\n    \
+                    /* multi\n         line */\n    y: f32[B];\n}\n";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn keeps_repeat_and_unroll() {
+    let source =
+        "constant { repeat i in 0..2 { w{i}: f32[D] @ref(\"layers.{i}.w\"); h{i}_scale: f32; } }\n\
+                  block entry { loop l (i in 0..2) @unroll { op add(x,w{i}) >> x; } \
+                  op add(x, w{N-1}) >> x; }";
+    let expected = "constant {\n    repeat i in 0..2 {\n        w{i}: f32[D] @ref(\"layers.{i}.w\");\n        \
+                    h{i}_scale: f32;\n    }\n}\n\n\
+                    block entry {\n    loop l (i in 0..2) @unroll {\n        op add(x, w{i}) >> x;\n    }\n    \
+                    op add(x, w{N-1}) >> x;\n}\n";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn orders_declaration_attributes() {
+    let source = "persistent {\n    w: f32[B,D] @table @ref(\"w\") @init(0.0);\n    \
+                  kv(l): f16[D] @fixed(l=0) @auto_dim(l)\n        @pattern(\"kv.*\");\n}\n\
+                  block entry { loop l (i in 0..2) @unroll { barrier; } }";
+    let expected = "persistent {\n    w: f32[B, D] @init(0.0) @ref(\"w\") @table;\n    \
+                    kv(l): f16[D] @pattern(\"kv.*\") @auto_dim(l) @fixed(l=0);\n}\n\n\
+                    block entry {\n    loop l (i in 0..2) @unroll {\n        barrier;\n    }\n}\n";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn keeps_single_blank_lines_between_statements() {
+    let source = "block entry {\n\n  barrier;\n\n\n  barrier;\n  barrier;\n}\nblock decode {}";
+    let expected =
+        "block entry {\n    barrier;\n\n    barrier;\n    barrier;\n}\n\nblock decode {}\n";
+    assert_formats(source, expected);
+}
+
+#[test]
+fn reports_invalid_sources_with_positions() {
+    let err = format_dsl("dynamic {\n  x: f32[B;\n}\n").expect_err("unbalanced source");
+    assert_eq!(
+        err.messages,
+        vec!["3:1: expected `]`, found `}`".to_string()]
+    );
+    let err = format_dsl("block entry {\n  barrier;\n").expect_err("unclosed source");
+    assert_eq!(err.messages, vec!["1:13: unclosed `{`".to_string()]);
+    let err = format_dsl("constant { w: f32 @ref(\"a); }").expect_err("unterminated string");
+    assert_eq!(
+        err.messages,
+        vec!["1:24: unterminated string literal".to_string()]
+    );
+}
+
+#[test]
+fn formats_graph_bodies_in_rust_sources() {
+    let source = "fn build() {\n    let g = graph! { dynamic{x:f32[B];} block entry{return;} };\n    \
+                  let h = openinfer::graph! {\n        // kept\n        block entry{repeat i in 0..2 {barrier;}}\n    };\n}\n";
+    let (output, notes) = match format_rust(source) {
+        Ok(formatted) => formatted,
+        Err(err) => panic!("invalid: {:?}", err.messages),
+    };
+    let expected = "fn build() {\n    let g = graph! {\n        dynamic {\n            x: f32[B];\n        }\n\n        \
+                    block entry {\n            return;\n        }\n    };\n    \
+                    let h = openinfer::graph! {\n        // kept\n        block entry {\n            \
+                    repeat i in 0..2 {\n                barrier;\n            }\n        }\n    };\n}\n";
+    assert_eq!(output, expected);
+    assert!(notes.is_empty());
+    assert_eq!(
+        format_rust(&output).map(|(output, _)| output).ok(),
+        Some(output)
+    );
+}
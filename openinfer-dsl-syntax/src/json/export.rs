@@ -0,0 +1,376 @@
+//! Graph to JSON value, following the schema in the module docs.
+
+use super::value::{Kind, Value};
+use super::{JSON_SCHEMA, JSON_VERSION};
+use crate::types::{
+    CacheAccess, CacheIndexExpr, CacheIndexValue, Dim, DimDecl, DimValue, GraphDsl, IndexExpr,
+    InitValue, LoopNode, MemoryKindToken, Node, OpAttrValue, RangeValue, Section, VarDecl, VarRef,
+};
+
+pub(crate) fn graph(graph: &GraphDsl) -> Value {
+    let sections = graph.sections.iter().filter_map(section).collect();
+    Value::object(vec![
+        ("schema", Value::string(JSON_SCHEMA)),
+        ("version", Value::int(JSON_VERSION.into())),
+        ("sections", Value::array(sections)),
+    ])
+}
+
+/// `fn` definitions have no section: their bodies appear where they are
+/// called.
+fn section(section: &Section) -> Option<Value> {
+    Some(match section {
+        Section::Dims(dims) => Value::object(vec![
+            ("section", Value::string("dims")),
+            (
+                "dims",
+                Value::array(dims.dims.iter().map(dim_decl).collect()),
+            ),
+        ]),
+        Section::Memory(mem) => Value::object(vec![
+            ("section", Value::string("memory")),
+            ("kind", Value::string(memory_kind(&mem.kind))),
+            ("vars", Value::array(mem.vars.iter().map(var).collect())),
+        ]),
+        Section::Block(block) => Value::object(vec![
+            ("section", Value::string("block")),
+            ("name", Value::string(&block.name)),
+            ("nodes", nodes(&block.nodes)),
+        ]),
+        Section::Func(_) => return None,
+    })
+}
+
+fn dim_decl(decl: &DimDecl) -> Value {
+    let mut members = vec![("name", Value::string(&decl.name))];
+    match &decl.value {
+        DimValue::Free => {}
+        DimValue::Fixed(lit) => members.push(("value", Value::number(lit.base10_digits()))),
+        DimValue::Range {
+            start,
+            end,
+            inclusive,
+        } => members.push((
+            "range",
+            Value::object(vec![
+                ("start", Value::number(start.base10_digits())),
+                ("end", Value::number(end.base10_digits())),
+                ("inclusive", Value::bool(*inclusive)),
+            ]),
+        )),
+    }
+    if !decl.constraints.is_empty() {
+        let constraints = decl
+            .constraints
+            .iter()
+            .map(|constraint| {
+                Value::object(vec![
+                    ("dividend", dim(&constraint.dividend)),
+                    ("divisor", dim(&constraint.divisor)),
+                ])
+            })
+            .collect();
+        members.push(("constraints", Value::array(constraints)));
+    }
+    Value::object(members)
+}
+
+fn memory_kind(kind: &MemoryKindToken) -> &'static str {
+    match kind {
+        MemoryKindToken::Dynamic => "dynamic",
+        MemoryKindToken::Volatile => "volatile",
+        MemoryKindToken::Constant => "constant",
+        MemoryKindToken::Persistent => "persistent",
+    }
+}
+
+fn var(var: &VarDecl) -> Value {
+    let mut members = vec![
+        ("name", Value::string(&var.name)),
+        ("dtype", Value::string(&var.dtype)),
+        ("dims", dims(&var.dims)),
+    ];
+    if let Some(init) = &var.init {
+        let init = match init {
+            InitValue::Float { lit, negative } => {
+                let mut digits = lit.base10_digits().to_string();
+                if digits.ends_with('.') {
+                    digits.push('0');
+                }
+                (
+                    "float",
+                    Value::number(format!("{}{}", sign(*negative), digits)),
+                )
+            }
+            InitValue::Int { lit, negative } => (
+                "int",
+                Value::number(format!("{}{}", sign(*negative), lit.base10_digits())),
+            ),
+            InitValue::Bool { lit } => ("bool", Value::bool(lit.value)),
+        };
+        members.push(("init", Value::object(vec![init])));
+    }
+    if let Some(ref_name) = &var.ref_name {
+        members.push(("ref", Value::string(ref_name.value())));
+    }
+    if let Some(pattern) = &var.pattern {
+        members.push(("pattern", Value::string(pattern.value())));
+    }
+    if !var.table_indices.is_empty() {
+        members.push(("table_indices", names(&var.table_indices)));
+    }
+    if var.table {
+        members.push(("table", Value::bool(true)));
+    }
+    if !var.auto_dim.is_empty() {
+        members.push(("auto_dim", names(&var.auto_dim)));
+    }
+    if !var.fixed.is_empty() {
+        let fixed = var
+            .fixed
+            .iter()
+            .map(|(name, value)| (name.to_string(), Value::number(value.base10_digits())))
+            .collect();
+        members.push(("fixed", Value::new(Kind::Object(fixed))));
+    }
+    Value::object(members)
+}
+
+fn sign(negative: bool) -> &'static str {
+    if negative {
+        "-"
+    } else {
+        ""
+    }
+}
+
+fn dims(dims: &[Dim]) -> Value {
+    Value::array(dims.iter().map(dim).collect())
+}
+
+fn dim(dim: &Dim) -> Value {
+    match dim {
+        Dim::Lit(lit) => Value::number(lit.base10_digits()),
+        _ => Value::string(dim),
+    }
+}
+
+fn names(names: &[syn::Ident]) -> Value {
+    Value::array(names.iter().map(Value::string).collect())
+}
+
+fn nodes(nodes: &[Node]) -> Value {
+    let mut out = Vec::new();
+    flatten(nodes, &mut out);
+    Value::array(out)
+}
+
+/// Push `nodes` as code generation walks them: unrolled loops and calls
+/// contribute their expanded bodies.
+fn flatten(nodes: &[Node], out: &mut Vec<Value>) {
+    for node in nodes {
+        match node {
+            Node::Loop(loop_node) if loop_node.unroll.is_some() => flatten(&loop_node.body, out),
+            Node::Call(call) => flatten(&call.inlined, out),
+            node => out.push(self::node(node)),
+        }
+    }
+}
+
+fn node(node: &Node) -> Value {
+    let (kind, mut members) = match node {
+        Node::Assign(assign) => (
+            "assign",
+            vec![
+                ("name", Value::string(&assign.name)),
+                ("dtype", Value::string(&assign.dtype)),
+                ("dims", dims(&assign.dims)),
+            ],
+        ),
+        Node::Op(op) => {
+            let settings = op
+                .settings
+                .iter()
+                .map(|setting| (setting.name.to_string(), setting_value(&setting.value)))
+                .collect();
+            (
+                "op",
+                vec![
+                    ("name", Value::string(&op.name)),
+                    (
+                        "inputs",
+                        Value::array(op.inputs.iter().map(var_ref).collect()),
+                    ),
+                    ("settings", Value::new(Kind::Object(settings))),
+                    ("output", Value::string(&op.output)),
+                ],
+            )
+        }
+        Node::Branch(branch) => {
+            let mut members = Vec::new();
+            if let Some(cond) = &branch.cond {
+                members.push(("cond", Value::string(cond)));
+            }
+            members.push(("then", Value::string(&branch.then_block)));
+            if let Some(else_block) = &branch.else_block {
+                members.push(("else", Value::string(else_block)));
+            }
+            ("branch", members)
+        }
+        Node::Barrier => ("barrier", Vec::new()),
+        Node::Dep(dep) => (
+            "dep",
+            vec![
+                ("after", Value::string(&dep.after)),
+                ("before", Value::string(&dep.before)),
+            ],
+        ),
+        Node::CacheRead(read) => (
+            "cache.read",
+            vec![
+                ("src", cache_access(&read.src)),
+                ("dst", var_ref(&read.dst)),
+            ],
+        ),
+        Node::CacheWrite(write) => (
+            "cache.write",
+            vec![
+                ("src", var_ref(&write.src)),
+                ("dst", cache_access(&write.dst)),
+            ],
+        ),
+        Node::CacheInc(inc) => (
+            "cache.increment",
+            vec![
+                ("target", Value::string(&inc.target)),
+                ("amount", Value::int(inc.amount)),
+            ],
+        ),
+        Node::CacheDec(dec) => (
+            "cache.decrement",
+            vec![
+                ("target", Value::string(&dec.target)),
+                ("amount", Value::int(dec.amount)),
+            ],
+        ),
+        Node::CacheReset(reset) => ("cache.reset", vec![("target", cache_access(&reset.target))]),
+        Node::Transfer(transfer) => (
+            "transfer",
+            vec![
+                ("src", var_ref(&transfer.src)),
+                ("dst", var_ref(&transfer.dst)),
+            ],
+        ),
+        Node::Loop(loop_node) => ("loop", loop_members(loop_node)),
+        Node::Call(_) => unreachable!("calls are flattened into their inlined nodes"),
+        Node::Yield(node) => ("yield", vec![("vars", names(&node.vars))]),
+        Node::Await(node) => ("await", vec![("vars", names(&node.vars))]),
+        Node::Return => ("return", Vec::new()),
+    };
+    members.insert(0, ("node", Value::string(kind)));
+    Value::object(members)
+}
+
+fn loop_members(node: &LoopNode) -> Vec<(&'static str, Value)> {
+    let mut members = vec![
+        ("name", Value::string(&node.name)),
+        ("index", Value::string(&node.index)),
+        ("start", range_value(&node.start)),
+        ("end", range_value(&node.end)),
+        ("inclusive", Value::bool(node.inclusive)),
+    ];
+    if let Some(step) = &node.step {
+        members.push(("step", Value::number(step.base10_digits())));
+    }
+    members.push(("body", nodes(&node.body)));
+    members
+}
+
+fn range_value(value: &RangeValue) -> Value {
+    match value {
+        RangeValue::Lit(lit) => Value::number(lit.base10_digits()),
+        value => Value::string(value),
+    }
+}
+
+fn var_ref(var: &VarRef) -> Value {
+    if var.indices.is_empty() {
+        return Value::string(&var.name);
+    }
+    let indices = var
+        .indices
+        .iter()
+        .map(|index| match index {
+            IndexExpr::Ident(ident) => Value::string(ident),
+            IndexExpr::Lit(lit) => Value::number(lit.base10_digits()),
+        })
+        .collect();
+    Value::object(vec![
+        ("name", Value::string(&var.name)),
+        ("indices", Value::array(indices)),
+    ])
+}
+
+fn cache_access(access: &CacheAccess) -> Value {
+    if !access.bracketed {
+        return Value::string(&access.name);
+    }
+    let indices = access
+        .indices
+        .iter()
+        .map(|index| match index {
+            CacheIndexExpr::Single(value) => cache_index_value(value),
+            CacheIndexExpr::Slice { start, end } => {
+                let mut members = Vec::new();
+                if let Some(start) = start {
+                    members.push(("start", cache_index_value(start)));
+                }
+                if let Some(end) = end {
+                    members.push(("end", cache_index_value(end)));
+                }
+                Value::object(members)
+            }
+        })
+        .collect();
+    Value::object(vec![
+        ("name", Value::string(&access.name)),
+        ("indices", Value::array(indices)),
+    ])
+}
+
+fn cache_index_value(value: &CacheIndexValue) -> Value {
+    match value {
+        CacheIndexValue::Ident(ident) => Value::string(ident),
+        CacheIndexValue::Lit(value) => Value::int(*value),
+    }
+}
+
+fn setting_value(value: &OpAttrValue) -> Value {
+    let (tag, value) = match value {
+        OpAttrValue::Float(value) => ("float", float(f64::from(*value))),
+        OpAttrValue::Double(value) => ("float", float(*value)),
+        OpAttrValue::Int(value) => ("int", Value::int(*value)),
+        OpAttrValue::Bool(value) => ("bool", Value::bool(*value)),
+        OpAttrValue::String(value) => ("str", Value::string(value)),
+        OpAttrValue::IntList(values) => (
+            "ints",
+            Value::array(values.iter().map(|value| Value::int(*value)).collect()),
+        ),
+        OpAttrValue::DTypeList(values) => ("dtypes", names(values)),
+        OpAttrValue::Var(ident) => ("var", Value::string(ident)),
+        OpAttrValue::VarList(values) => ("vars", names(values)),
+    };
+    Value::object(vec![(tag, value)])
+}
+
+/// JSON has no infinities or NaN, so those are strings.
+fn float(value: f64) -> Value {
+    if value.is_nan() {
+        Value::string("nan")
+    } else if value.is_infinite() {
+        Value::string(if value > 0.0 { "inf" } else { "-inf" })
+    } else {
+        // `{:?}` keeps the decimal point, so the value reads back as a float.
+        Value::number(format!("{:?}", value))
+    }
+}
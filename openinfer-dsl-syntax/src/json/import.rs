@@ -0,0 +1,571 @@
+//! JSON value to graph, following the schema in the module docs.
+//!
+//! Dimension expressions, loop bounds and integer literals go through the
+//! DSL parsers, so they read exactly as they would in a `.oinf` file.
+
+use proc_macro2::Span;
+use syn::parse::Parser;
+use syn::{Ident, LitBool, LitFloat, LitInt, LitStr};
+
+use super::value::{Kind, Value};
+use super::{JsonError, JSON_SCHEMA, JSON_VERSION};
+use crate::parsers::dims::parse_dim_expr;
+use crate::parsers::range::parse_range_value;
+use crate::types::{
+    AssignNode, AwaitNode, BlockSection, BranchNode, CacheAccess, CacheDecNode, CacheIncNode,
+    CacheIndexExpr, CacheIndexValue, CacheReadNode, CacheResetNode, CacheWriteNode, DepNode, Dim,
+    DimConstraint, DimDecl, DimValue, DimsSection, GraphDsl, IndexExpr, InitValue, LoopNode,
+    MemoryKindToken, MemorySection, Node, OpAttrValue, OpNode, OpSetting, RangeValue, Section,
+    TransferNode, VarDecl, VarRef, YieldNode,
+};
+
+type Result<T> = std::result::Result<T, JsonError>;
+
+pub(crate) fn graph(value: &Value) -> Result<GraphDsl> {
+    let fields = fields(value, &["schema", "version", "sections"])?;
+    let schema = fields.req("schema")?;
+    if string(schema)? != JSON_SCHEMA {
+        return Err(schema.error(format!("expected schema \"{}\"", JSON_SCHEMA)));
+    }
+    let version = fields.req("version")?;
+    if int(version)? != i64::from(JSON_VERSION) {
+        return Err(version.error(format!(
+            "unsupported version {} (expected {})",
+            int(version)?,
+            JSON_VERSION
+        )));
+    }
+    let sections = array(fields.req("sections")?)?
+        .iter()
+        .map(section)
+        .collect::<Result<_>>()?;
+    Ok(GraphDsl { sections })
+}
+
+/// The members of an object that has no fields besides `allowed`.
+struct Fields<'a> {
+    value: &'a Value,
+    members: &'a [(String, Value)],
+}
+
+fn fields<'a>(value: &'a Value, allowed: &[&str]) -> Result<Fields<'a>> {
+    let Kind::Object(members) = &value.kind else {
+        return Err(value.error("expected an object"));
+    };
+    for (key, member) in members {
+        if !allowed.contains(&key.as_str()) {
+            return Err(member.error(format!("unknown field `{}`", key)));
+        }
+    }
+    Ok(Fields { value, members })
+}
+
+impl<'a> Fields<'a> {
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.members
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    fn req(&self, key: &str) -> Result<&'a Value> {
+        self.get(key)
+            .ok_or_else(|| self.value.error(format!("missing field `{}`", key)))
+    }
+}
+
+fn string(value: &Value) -> Result<&str> {
+    match &value.kind {
+        Kind::String(text) => Ok(text),
+        _ => Err(value.error("expected a string")),
+    }
+}
+
+fn boolean(value: &Value) -> Result<bool> {
+    match value.kind {
+        Kind::Bool(value) => Ok(value),
+        _ => Err(value.error("expected a boolean")),
+    }
+}
+
+fn array(value: &Value) -> Result<&[Value]> {
+    match &value.kind {
+        Kind::Array(items) => Ok(items),
+        _ => Err(value.error("expected an array")),
+    }
+}
+
+/// Text of an integer number.
+fn int_text(value: &Value) -> Result<&str> {
+    match &value.kind {
+        Kind::Number(text) if !text.contains(['.', 'e', 'E']) => Ok(text),
+        _ => Err(value.error("expected an integer")),
+    }
+}
+
+fn int(value: &Value) -> Result<i64> {
+    int_text(value)?
+        .parse()
+        .map_err(|_| value.error("integer out of range"))
+}
+
+fn ints(value: &Value) -> Result<Vec<i64>> {
+    array(value)?.iter().map(int).collect()
+}
+
+fn lit_int(value: &Value) -> Result<LitInt> {
+    let text = int_text(value)?;
+    syn::parse_str(text).map_err(|err| value.error(format!("invalid integer `{}`: {}", text, err)))
+}
+
+fn ident(value: &Value) -> Result<Ident> {
+    let text = string(value)?;
+    syn::parse_str(text).map_err(|_| value.error(format!("`{}` is not an identifier", text)))
+}
+
+fn idents(value: &Value) -> Result<Vec<Ident>> {
+    array(value)?.iter().map(ident).collect()
+}
+
+fn lit_str(value: &Value) -> Result<LitStr> {
+    Ok(LitStr::new(string(value)?, Span::call_site()))
+}
+
+/// DSL text of an integer or dimension expression string.
+fn expr_text(value: &Value) -> Result<&str> {
+    match &value.kind {
+        Kind::Number(_) => int_text(value),
+        Kind::String(text) => Ok(text),
+        _ => Err(value.error("expected an integer or a dimension expression")),
+    }
+}
+
+fn dim(value: &Value) -> Result<Dim> {
+    let text = expr_text(value)?;
+    parse_dim_expr
+        .parse_str(text)
+        .map_err(|err| value.error(format!("invalid dimension `{}`: {}", text, err)))
+}
+
+fn dims(value: &Value) -> Result<Vec<Dim>> {
+    array(value)?.iter().map(dim).collect()
+}
+
+fn range_value(value: &Value) -> Result<RangeValue> {
+    let text = expr_text(value)?;
+    parse_range_value
+        .parse_str(text)
+        .map_err(|err| value.error(format!("invalid loop bound `{}`: {}", text, err)))
+}
+
+fn section(value: &Value) -> Result<Section> {
+    let tag =
+        fields(value, &["section", "dims", "kind", "vars", "name", "nodes"])?.req("section")?;
+    Ok(match string(tag)? {
+        "dims" => {
+            let fields = fields(value, &["section", "dims"])?;
+            let dims = array(fields.req("dims")?)?
+                .iter()
+                .map(dim_decl)
+                .collect::<Result<_>>()?;
+            Section::Dims(DimsSection { dims })
+        }
+        "memory" => {
+            let fields = fields(value, &["section", "kind", "vars"])?;
+            let kind = fields.req("kind")?;
+            let kind = match string(kind)? {
+                "dynamic" => MemoryKindToken::Dynamic,
+                "volatile" => MemoryKindToken::Volatile,
+                "constant" => MemoryKindToken::Constant,
+                "persistent" => MemoryKindToken::Persistent,
+                other => return Err(kind.error(format!("unknown memory kind `{}`", other))),
+            };
+            let vars = array(fields.req("vars")?)?
+                .iter()
+                .map(var)
+                .collect::<Result<_>>()?;
+            Section::Memory(MemorySection { kind, vars })
+        }
+        "block" => {
+            let fields = fields(value, &["section", "name", "nodes"])?;
+            Section::Block(BlockSection {
+                name: ident(fields.req("name")?)?,
+                nodes: nodes(fields.req("nodes")?)?,
+            })
+        }
+        other => return Err(tag.error(format!("unknown section `{}`", other))),
+    })
+}
+
+fn dim_decl(value: &Value) -> Result<DimDecl> {
+    let fields = fields(value, &["name", "value", "range", "constraints"])?;
+    let value = match (fields.get("value"), fields.get("range")) {
+        (None, None) => DimValue::Free,
+        (Some(fixed), None) => DimValue::Fixed(lit_int(fixed)?),
+        (None, Some(range)) => {
+            let range = self::fields(range, &["start", "end", "inclusive"])?;
+            DimValue::Range {
+                start: lit_int(range.req("start")?)?,
+                end: lit_int(range.req("end")?)?,
+                inclusive: boolean(range.req("inclusive")?)?,
+            }
+        }
+        (Some(_), Some(range)) => return Err(range.error("a dim has either `value` or `range`")),
+    };
+    let constraints = match fields.get("constraints") {
+        Some(constraints) => array(constraints)?
+            .iter()
+            .map(|constraint| {
+                let constraint = self::fields(constraint, &["dividend", "divisor"])?;
+                Ok(DimConstraint {
+                    dividend: dim(constraint.req("dividend")?)?,
+                    divisor: dim(constraint.req("divisor")?)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+        None => Vec::new(),
+    };
+    Ok(DimDecl {
+        name: ident(fields.req("name")?)?,
+        value,
+        constraints,
+    })
+}
+
+fn var(value: &Value) -> Result<VarDecl> {
+    let fields = fields(
+        value,
+        &[
+            "name",
+            "dtype",
+            "dims",
+            "init",
+            "ref",
+            "pattern",
+            "table_indices",
+            "table",
+            "auto_dim",
+            "fixed",
+        ],
+    )?;
+    let fixed = match fields.get("fixed") {
+        Some(fixed) => {
+            let Kind::Object(members) = &fixed.kind else {
+                return Err(fixed.error("expected an object"));
+            };
+            members
+                .iter()
+                .map(|(name, value)| {
+                    let name = syn::parse_str(name)
+                        .map_err(|_| value.error(format!("`{}` is not an identifier", name)))?;
+                    Ok((name, lit_int(value)?))
+                })
+                .collect::<Result<_>>()?
+        }
+        None => Vec::new(),
+    };
+    Ok(VarDecl {
+        name: ident(fields.req("name")?)?,
+        dtype: ident(fields.req("dtype")?)?,
+        dims: dims(fields.req("dims")?)?,
+        init: fields.get("init").map(init).transpose()?,
+        ref_name: fields.get("ref").map(lit_str).transpose()?,
+        pattern: fields.get("pattern").map(lit_str).transpose()?,
+        table_indices: optional_idents(&fields, "table_indices")?,
+        table: fields
+            .get("table")
+            .map(boolean)
+            .transpose()?
+            .unwrap_or(false),
+        auto_dim: optional_idents(&fields, "auto_dim")?,
+        fixed,
+    })
+}
+
+fn optional_idents(fields: &Fields, key: &str) -> Result<Vec<Ident>> {
+    Ok(fields.get(key).map(idents).transpose()?.unwrap_or_default())
+}
+
+fn init(value: &Value) -> Result<InitValue> {
+    let (tag, inner) = tagged(value)?;
+    match tag {
+        "float" => {
+            let Kind::Number(text) = &inner.kind else {
+                return Err(inner.error("expected a number"));
+            };
+            let (negative, digits) = split_sign(text);
+            let mut digits = digits.to_string();
+            if !digits.contains(['.', 'e', 'E']) {
+                digits.push_str(".0");
+            }
+            Ok(InitValue::Float {
+                lit: LitFloat::new(&digits, Span::call_site()),
+                negative,
+            })
+        }
+        "int" => {
+            let (negative, digits) = split_sign(int_text(inner)?);
+            Ok(InitValue::Int {
+                lit: LitInt::new(digits, Span::call_site()),
+                negative,
+            })
+        }
+        "bool" => Ok(InitValue::Bool {
+            lit: LitBool::new(boolean(inner)?, Span::call_site()),
+        }),
+        other => Err(value.error(format!("unknown init kind `{}`", other))),
+    }
+}
+
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    }
+}
+
+/// The only member of a single-member object such as `{"int": 1}`.
+fn tagged(value: &Value) -> Result<(&str, &Value)> {
+    match &value.kind {
+        Kind::Object(members) if members.len() == 1 => Ok((&members[0].0, &members[0].1)),
+        _ => Err(value.error("expected an object with a single field")),
+    }
+}
+
+fn nodes(value: &Value) -> Result<Vec<Node>> {
+    array(value)?.iter().map(node).collect()
+}
+
+fn node(value: &Value) -> Result<Node> {
+    let Kind::Object(members) = &value.kind else {
+        return Err(value.error("expected an object"));
+    };
+    let tag = members
+        .iter()
+        .find(|(key, _)| key == "node")
+        .map(|(_, tag)| tag)
+        .ok_or_else(|| value.error("missing field `node`"))?;
+    Ok(match string(tag)? {
+        "assign" => {
+            let fields = fields(value, &["node", "name", "dtype", "dims"])?;
+            Node::Assign(AssignNode {
+                name: ident(fields.req("name")?)?,
+                dtype: ident(fields.req("dtype")?)?,
+                dims: dims(fields.req("dims")?)?,
+            })
+        }
+        "op" => {
+            let fields = fields(value, &["node", "name", "inputs", "settings", "output"])?;
+            let settings = fields.req("settings")?;
+            let Kind::Object(settings) = &settings.kind else {
+                return Err(settings.error("expected an object"));
+            };
+            let settings = settings
+                .iter()
+                .map(|(name, value)| {
+                    let name = syn::parse_str(name)
+                        .map_err(|_| value.error(format!("`{}` is not an identifier", name)))?;
+                    Ok(OpSetting {
+                        name,
+                        value: setting_value(value)?,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Node::Op(OpNode {
+                name: ident(fields.req("name")?)?,
+                inputs: array(fields.req("inputs")?)?
+                    .iter()
+                    .map(var_ref)
+                    .collect::<Result<_>>()?,
+                settings,
+                output: ident(fields.req("output")?)?,
+            })
+        }
+        "branch" => {
+            let fields = fields(value, &["node", "cond", "then", "else"])?;
+            Node::Branch(BranchNode {
+                cond: fields.get("cond").map(ident).transpose()?,
+                then_block: ident(fields.req("then")?)?,
+                else_block: fields.get("else").map(ident).transpose()?,
+            })
+        }
+        "barrier" => {
+            fields(value, &["node"])?;
+            Node::Barrier
+        }
+        "dep" => {
+            let fields = fields(value, &["node", "after", "before"])?;
+            Node::Dep(DepNode {
+                after: ident(fields.req("after")?)?,
+                before: ident(fields.req("before")?)?,
+            })
+        }
+        "cache.read" => {
+            let fields = fields(value, &["node", "src", "dst"])?;
+            Node::CacheRead(CacheReadNode {
+                src: cache_access(fields.req("src")?)?,
+                dst: var_ref(fields.req("dst")?)?,
+            })
+        }
+        "cache.write" => {
+            let fields = fields(value, &["node", "src", "dst"])?;
+            Node::CacheWrite(CacheWriteNode {
+                src: var_ref(fields.req("src")?)?,
+                dst: cache_access(fields.req("dst")?)?,
+            })
+        }
+        "cache.increment" => {
+            let fields = fields(value, &["node", "target", "amount"])?;
+            Node::CacheInc(CacheIncNode {
+                target: ident(fields.req("target")?)?,
+                amount: int(fields.req("amount")?)?,
+            })
+        }
+        "cache.decrement" => {
+            let fields = fields(value, &["node", "target", "amount"])?;
+            Node::CacheDec(CacheDecNode {
+                target: ident(fields.req("target")?)?,
+                amount: int(fields.req("amount")?)?,
+            })
+        }
+        "cache.reset" => {
+            let fields = fields(value, &["node", "target"])?;
+            Node::CacheReset(CacheResetNode {
+                target: cache_access(fields.req("target")?)?,
+            })
+        }
+        "transfer" => {
+            let fields = fields(value, &["node", "src", "dst"])?;
+            Node::Transfer(TransferNode {
+                src: var_ref(fields.req("src")?)?,
+                dst: var_ref(fields.req("dst")?)?,
+            })
+        }
+        "loop" => {
+            let fields = fields(
+                value,
+                &[
+                    "node",
+                    "name",
+                    "index",
+                    "start",
+                    "end",
+                    "inclusive",
+                    "step",
+                    "body",
+                ],
+            )?;
+            let step = match fields.get("step") {
+                Some(step) if int(step)? == 0 => {
+                    return Err(step.error("loop step must not be zero"))
+                }
+                step => step.map(lit_int).transpose()?,
+            };
+            Node::Loop(LoopNode {
+                name: ident(fields.req("name")?)?,
+                index: ident(fields.req("index")?)?,
+                start: range_value(fields.req("start")?)?,
+                end: range_value(fields.req("end")?)?,
+                inclusive: boolean(fields.req("inclusive")?)?,
+                step,
+                unroll: None,
+                body: nodes(fields.req("body")?)?,
+            })
+        }
+        "yield" => {
+            let fields = fields(value, &["node", "vars"])?;
+            Node::Yield(YieldNode {
+                vars: idents(fields.req("vars")?)?,
+            })
+        }
+        "await" => {
+            let fields = fields(value, &["node", "vars"])?;
+            Node::Await(AwaitNode {
+                vars: idents(fields.req("vars")?)?,
+            })
+        }
+        "return" => {
+            fields(value, &["node"])?;
+            Node::Return
+        }
+        other => return Err(tag.error(format!("unknown node `{}`", other))),
+    })
+}
+
+fn var_ref(value: &Value) -> Result<VarRef> {
+    if let Kind::String(_) = value.kind {
+        return Ok(VarRef {
+            name: ident(value)?,
+            indices: Vec::new(),
+        });
+    }
+    let fields = fields(value, &["name", "indices"])?;
+    let indices = array(fields.req("indices")?)?
+        .iter()
+        .map(|index| match index.kind {
+            Kind::String(_) => ident(index).map(IndexExpr::Ident),
+            _ => lit_int(index).map(IndexExpr::Lit),
+        })
+        .collect::<Result<_>>()?;
+    Ok(VarRef {
+        name: ident(fields.req("name")?)?,
+        indices,
+    })
+}
+
+fn cache_access(value: &Value) -> Result<CacheAccess> {
+    if let Kind::String(_) = value.kind {
+        return Ok(CacheAccess {
+            name: ident(value)?,
+            indices: Vec::new(),
+            bracketed: false,
+        });
+    }
+    let fields = fields(value, &["name", "indices"])?;
+    let indices = array(fields.req("indices")?)?
+        .iter()
+        .map(|index| match index.kind {
+            Kind::Object(_) => {
+                let slice = self::fields(index, &["start", "end"])?;
+                Ok(CacheIndexExpr::Slice {
+                    start: slice.get("start").map(cache_index_value).transpose()?,
+                    end: slice.get("end").map(cache_index_value).transpose()?,
+                })
+            }
+            _ => cache_index_value(index).map(CacheIndexExpr::Single),
+        })
+        .collect::<Result<_>>()?;
+    Ok(CacheAccess {
+        name: ident(fields.req("name")?)?,
+        indices,
+        bracketed: true,
+    })
+}
+
+fn cache_index_value(value: &Value) -> Result<CacheIndexValue> {
+    match value.kind {
+        Kind::String(_) => ident(value).map(CacheIndexValue::Ident),
+        _ => int(value).map(CacheIndexValue::Lit),
+    }
+}
+
+fn setting_value(value: &Value) -> Result<OpAttrValue> {
+    let (tag, inner) = tagged(value)?;
+    Ok(match tag {
+        "float" => OpAttrValue::Double(match &inner.kind {
+            Kind::Number(text) => text.parse().map_err(|_| inner.error("invalid float"))?,
+            Kind::String(text) if text == "inf" => f64::INFINITY,
+            Kind::String(text) if text == "-inf" => f64::NEG_INFINITY,
+            Kind::String(text) if text == "nan" => f64::NAN,
+            _ => return Err(inner.error("expected a number, \"inf\", \"-inf\" or \"nan\"")),
+        }),
+        "int" => OpAttrValue::Int(int(inner)?),
+        "bool" => OpAttrValue::Bool(boolean(inner)?),
+        "str" => OpAttrValue::String(string(inner)?.to_string()),
+        "ints" => OpAttrValue::IntList(ints(inner)?),
+        "dtypes" => OpAttrValue::DTypeList(idents(inner)?),
+        "var" => OpAttrValue::Var(ident(inner)?),
+        "vars" => OpAttrValue::VarList(idents(inner)?),
+        other => return Err(value.error(format!("unknown setting kind `{}`", other))),
+    })
+}
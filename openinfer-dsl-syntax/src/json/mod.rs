@@ -0,0 +1,111 @@
+//! A versioned JSON form of a parsed graph, for tools that do not link Rust.
+//!
+//! [`to_json`] writes a graph and [`from_json`] reads one back. The JSON
+//! describes the graph as code generation sees it: `call`s are replaced by
+//! their inlined nodes, `@unroll` loops by their expanded bodies and `fn`
+//! definitions are left out, so `from_json(to_json(graph))` expands to the
+//! same code as `graph`.
+//!
+//! ## Schema, version 1
+//!
+//! ```text
+//! {
+//!   "schema": "openinfer-dsl/graph",
+//!   "version": 1,
+//!   "sections": [SECTION...]
+//! }
+//! ```
+//!
+//! Sections keep their source order and are tagged by `"section"`:
+//!
+//! - `{"section": "dims", "dims": [DIM...]}`, where a `DIM` is
+//!   `{"name", "value"?: INT, "range"?: {"start": INT, "end": INT, "inclusive": BOOL},
+//!   "constraints"?: [{"dividend": SHAPE_DIM, "divisor": SHAPE_DIM}...]}`; a
+//!   dim without `value` or `range` is free.
+//! - `{"section": "memory", "kind": "dynamic" | "volatile" | "constant" | "persistent",
+//!   "vars": [VAR...]}`, where a `VAR` is `{"name", "dtype", "dims": [SHAPE_DIM...]}`
+//!   plus the attributes it has: `"init": {"float": NUMBER} | {"int": INT} | {"bool": BOOL}`,
+//!   `"ref": STRING`, `"pattern": STRING`, `"table_indices": [NAME...]`,
+//!   `"table": true`, `"auto_dim": [NAME...]` and `"fixed": {NAME: INT...}`.
+//! - `{"section": "block", "name", "nodes": [NODE...]}`.
+//!
+//! A `SHAPE_DIM` is an integer or a dimension expression in DSL syntax such
+//! as `"B"` or `"ceil(S/2)"`. Nodes are tagged by `"node"`:
+//!
+//! ```text
+//! {"node": "assign", "name", "dtype", "dims": [SHAPE_DIM...]}
+//! {"node": "op", "name", "inputs": [REF...], "settings": {NAME: SETTING...}, "output"}
+//! {"node": "branch", "cond"?, "then", "else"?}
+//! {"node": "barrier"}
+//! {"node": "dep", "after", "before"}
+//! {"node": "cache.read", "src": CACHE, "dst": REF}
+//! {"node": "cache.write", "src": REF, "dst": CACHE}
+//! {"node": "cache.increment" | "cache.decrement", "target", "amount": INT}
+//! {"node": "cache.reset", "target": CACHE}
+//! {"node": "transfer", "src": REF, "dst": REF}
+//! {"node": "loop", "name", "index", "start": BOUND, "end": BOUND, "inclusive": BOOL,
+//!  "step"?: INT, "body": [NODE...]}
+//! {"node": "yield" | "await", "vars": [NAME...]}
+//! {"node": "return"}
+//! ```
+//!
+//! - `REF` is a variable name, or `{"name", "indices": [NAME | INT...]}` when
+//!   indexed.
+//! - `CACHE` is a cache name, or `{"name", "indices": [INDEX...]}` for a
+//!   bracketed access, where an `INDEX` is a name, an integer or a slice
+//!   `{"start"?: NAME | INT, "end"?: NAME | INT}`.
+//! - `BOUND` is an integer or a dimension expression string.
+//! - `SETTING` is one of `{"float": NUMBER | "inf" | "-inf" | "nan"}`,
+//!   `{"int": INT}`, `{"bool": BOOL}`, `{"str": STRING}`, `{"ints": [INT...]}`,
+//!   `{"dtypes": [NAME...]}`, `{"var": NAME}` and `{"vars": [NAME...]}`.
+//!
+//! Unknown fields are rejected, so a file written for a later version fails
+//! to load instead of losing information.
+
+use std::fmt;
+
+mod export;
+mod import;
+mod value;
+
+use crate::types::GraphDsl;
+
+/// Value of the `"schema"` field.
+pub const JSON_SCHEMA: &str = "openinfer-dsl/graph";
+
+/// Value of the `"version"` field written by [`to_json`], the only version
+/// [`from_json`] reads.
+pub const JSON_VERSION: u32 = 1;
+
+/// Render `graph` as pretty-printed JSON, ending with a newline.
+pub fn to_json(graph: &GraphDsl) -> String {
+    format!("{}\n", export::graph(graph))
+}
+
+/// Read a graph written by [`to_json`].
+///
+/// Only the structure is checked; run [`validate`](crate::validation::validate)
+/// on the result before using it. Identifiers and literals get call-site
+/// spans.
+pub fn from_json(text: &str) -> Result<GraphDsl, JsonError> {
+    import::graph(&value::parse(text)?)
+}
+
+/// Error of [`from_json`], at a 1-based line and column of the JSON text.
+#[derive(Debug)]
+pub struct JsonError {
+    /// Line of the offending value.
+    pub line: usize,
+    /// Column of the offending value.
+    pub column: usize,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
@@ -0,0 +1,395 @@
+//! A minimal JSON value with source positions, its parser and its writer.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::JsonError;
+
+/// A JSON value. Parsed values remember where they start; constructed ones
+/// are at line 0.
+pub(crate) struct Value {
+    pub(crate) kind: Kind,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+pub(crate) enum Kind {
+    Null,
+    Bool(bool),
+    /// Number text as written, so integer and float literals stay apart.
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    /// Members in document order.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn new(kind: Kind) -> Self {
+        Value {
+            kind,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn bool(value: bool) -> Self {
+        Value::new(Kind::Bool(value))
+    }
+
+    pub(crate) fn int(value: i64) -> Self {
+        Value::new(Kind::Number(value.to_string()))
+    }
+
+    pub(crate) fn number(text: impl Into<String>) -> Self {
+        Value::new(Kind::Number(text.into()))
+    }
+
+    pub(crate) fn string(value: impl Display) -> Self {
+        Value::new(Kind::String(value.to_string()))
+    }
+
+    pub(crate) fn array(items: Vec<Value>) -> Self {
+        Value::new(Kind::Array(items))
+    }
+
+    pub(crate) fn object(members: Vec<(&str, Value)>) -> Self {
+        Value::new(Kind::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        ))
+    }
+
+    /// An error located at this value.
+    pub(crate) fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self.kind, Kind::Array(_) | Kind::Object(_))
+    }
+
+    fn write(&self, out: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth + 1);
+        match &self.kind {
+            Kind::Null => out.write_str("null"),
+            Kind::Bool(value) => write!(out, "{}", value),
+            Kind::Number(text) => out.write_str(text),
+            Kind::String(value) => write_string(out, value),
+            Kind::Array(items) if items.is_empty() => out.write_str("[]"),
+            Kind::Array(items) if items.iter().all(Value::is_scalar) => {
+                out.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(", ")?;
+                    }
+                    item.write(out, depth)?;
+                }
+                out.write_char(']')
+            }
+            Kind::Array(items) => {
+                out.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    out.write_str(&indent)?;
+                    item.write(out, depth + 1)?;
+                    out.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{}]", "  ".repeat(depth))
+            }
+            Kind::Object(members) if members.is_empty() => out.write_str("{}"),
+            Kind::Object(members) => {
+                out.write_str("{\n")?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    out.write_str(&indent)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write(out, depth + 1)?;
+                    out.write_str(if i + 1 < members.len() { ",\n" } else { "\n" })?;
+                }
+                write!(out, "{}}}", "  ".repeat(depth))
+            }
+        }
+    }
+}
+
+/// Pretty-printed with two-space indentation; arrays of scalars stay on one
+/// line.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn write_string(out: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Deepest nesting of arrays and objects [`parse`] accepts, so hostile input
+/// cannot overflow the stack; the same limit as `serde_json`.
+const MAX_DEPTH: usize = 128;
+
+/// Parse a complete JSON document.
+pub(crate) fn parse(text: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        line: 1,
+        column: 1,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("trailing characters after JSON document"));
+    }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    /// Arrays and objects currently open.
+    depth: usize,
+}
+
+impl Parser {
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", expected)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+        let kind = match self.peek() {
+            Some('{') => self.nested(Parser::object)?,
+            Some('[') => self.nested(Parser::array)?,
+            Some('"') => Kind::String(self.string()?),
+            Some('-' | '0'..='9') => Kind::Number(self.number()?),
+            Some('t') => self.keyword("true", Kind::Bool(true))?,
+            Some('f') => self.keyword("false", Kind::Bool(false))?,
+            Some('n') => self.keyword("null", Kind::Null)?,
+            Some(_) => return Err(self.error("expected a JSON value")),
+            None => return Err(self.error("unexpected end of JSON document")),
+        };
+        Ok(Value { kind, line, column })
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Parser) -> Result<Kind, JsonError>,
+    ) -> Result<Kind, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let kind = parse(self)?;
+        self.depth -= 1;
+        Ok(kind)
+    }
+
+    fn keyword(&mut self, word: &str, kind: Kind) -> Result<Kind, JsonError> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error("expected a JSON value"));
+            }
+            self.bump();
+        }
+        Ok(kind)
+    }
+
+    fn object(&mut self) -> Result<Kind, JsonError> {
+        self.expect('{')?;
+        let mut members: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(Kind::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            if members.iter().any(|(existing, _)| *existing == key) {
+                return Err(self.error(format!("duplicate key `{}`", key)));
+            }
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.value()?;
+            members.push((key, value));
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some('}') => return Ok(Kind::Object(members)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Kind, JsonError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(Kind::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => return Ok(Kind::Array(items)),
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    out.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = self
+                .bump()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.bump() != Some('\\') || self.bump() != Some('u') {
+                return Err(self.error("unpaired surrogate in unicode escape"));
+            }
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("unpaired surrogate in unicode escape"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn number(&mut self) -> Result<String, JsonError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.bump();
+        }
+        if !self.digits() {
+            return Err(self.error("invalid number"));
+        }
+        if self.peek() == Some('.') {
+            self.bump();
+            if !self.digits() {
+                return Err(self.error("invalid number"));
+            }
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            if !self.digits() {
+                return Err(self.error("invalid number"));
+            }
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn digits(&mut self) -> bool {
+        let start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.bump();
+        }
+        self.pos > start
+    }
+}
@@ -0,0 +1,254 @@
+use syn::parse_str;
+
+use crate::json::{from_json, to_json};
+use crate::types::GraphDsl;
+use crate::validation::validate;
+
+fn parse_graph(src: &str) -> GraphDsl {
+    parse_str::<GraphDsl>(src).expect("parse graph")
+}
+
+#[test]
+fn writes_versioned_json() {
+    let graph = parse_graph(
+        r#"
+        dims { B: 1..=8; D = 64; }
+        dynamic { x: f32[B, D]; }
+        volatile { scale: f32 @init(-0.5); }
+        block entry {
+            assign t: f32[B, D*2];
+            op scale(x, factor=2.0, bias=scale) >> t;
+            loop l (i in 0..B step 2) { cache.read x[i, 1..] >> t; }
+            return;
+        }
+        "#,
+    );
+    let expected = r#"{
+  "schema": "openinfer-dsl/graph",
+  "version": 1,
+  "sections": [
+    {
+      "section": "dims",
+      "dims": [
+        {
+          "name": "B",
+          "range": {
+            "start": 1,
+            "end": 8,
+            "inclusive": true
+          }
+        },
+        {
+          "name": "D",
+          "value": 64
+        }
+      ]
+    },
+    {
+      "section": "memory",
+      "kind": "dynamic",
+      "vars": [
+        {
+          "name": "x",
+          "dtype": "f32",
+          "dims": ["B", "D"]
+        }
+      ]
+    },
+    {
+      "section": "memory",
+      "kind": "volatile",
+      "vars": [
+        {
+          "name": "scale",
+          "dtype": "f32",
+          "dims": [],
+          "init": {
+            "float": -0.5
+          }
+        }
+      ]
+    },
+    {
+      "section": "block",
+      "name": "entry",
+      "nodes": [
+        {
+          "node": "assign",
+          "name": "t",
+          "dtype": "f32",
+          "dims": ["B", "D*2"]
+        },
+        {
+          "node": "op",
+          "name": "scale",
+          "inputs": ["x"],
+          "settings": {
+            "factor": {
+              "float": 2.0
+            },
+            "bias": {
+              "var": "scale"
+            }
+          },
+          "output": "t"
+        },
+        {
+          "node": "loop",
+          "name": "l",
+          "index": "i",
+          "start": 0,
+          "end": "B",
+          "inclusive": false,
+          "step": 2,
+          "body": [
+            {
+              "node": "cache.read",
+              "src": {
+                "name": "x",
+                "indices": [
+                  "i",
+                  {
+                    "start": 1
+                  }
+                ]
+              },
+              "dst": "t"
+            }
+          ]
+        },
+        {
+          "node": "return"
+        }
+      ]
+    }
+  ]
+}
+"#;
+    assert_eq!(to_json(&graph), expected);
+}
+
+const ROUND_TRIP_SRC: &str = r#"
+dims { B: 1..=64; S: 1..4096; D = 768; H where D % H == 0, (D+H)/2 % H == 0; }
+
+fn mlp(x: f32[B, D], w: f32[D, D]) -> f32[B, D] {
+    assign h: f32[B, D];
+    op matmul(x, w, acc=[f32]) >> h;
+    return h;
+}
+
+dynamic { x: f32[B, D]; y: i32[4, B*D]; }
+volatile { tmp: f32[B, D] @init(-0.5); n: i64 @init(3); flag: bool @init(true); }
+constant {
+    repeat i in 0..2 { w{i}: f32[D, D] @ref("layers.{i}.w") @pattern("gauss"); }
+    half: f16[ceil(S/8), (S-1)*2];
+}
+persistent {
+    state(i, j): f32[B, D] @table @auto_dim(j) @fixed(i=2);
+    steps: i64;
+}
+
+block entry {
+    assign t: f32[B, D];
+    op add(x, tmp) >> t;
+    op relu(t, alpha=0.1, clamp_max=inf) >> t;
+    op sum(t, axes=[-1, 0], keepdims=true, mode="fast") >> t;
+    cache.read state[0, ..j] >> t;
+    cache.write t >> state[i, 2..];
+    cache.increment 2 steps;
+    cache.decrement steps;
+    cache.reset state[.., -1];
+    transfer x >> tmp;
+    loop layers (l in 0..S-1 step 2) {
+        loop inner (k in N..=0 step -1) { barrier; }
+        dep after(x) before(t);
+    }
+    loop unrolled (u in 0..2) @unroll { op add(x, w{u}) >> x; }
+    call mlp(x, w0) >> x;
+    branch flag entry decode;
+    yield x, t;
+    await x;
+    branch decode;
+}
+
+block decode { return; }
+"#;
+
+#[test]
+fn json_round_trips_through_from_json() {
+    let graph = parse_graph(ROUND_TRIP_SRC);
+    let json = to_json(&graph);
+    let imported = from_json(&json).expect("read json");
+    assert_eq!(to_json(&imported), json);
+
+    // Calls and `@unroll` loops are stored expanded and fns are dropped.
+    let printed = imported.to_string();
+    assert!(!printed.contains("fn mlp"));
+    assert!(!printed.contains("call mlp"));
+    assert!(printed.contains("    op add(x, w0) >> x;\n    op add(x, w1) >> x;\n"));
+    assert!(printed.contains("op matmul(x, w0, acc=[f32])"));
+    assert!(printed.contains("    state(i, j): f32[B, D] @table @auto_dim(j) @fixed(i=2);\n"));
+    assert!(printed.contains("    half: f16[ceil(S/8), (S-1)*2];\n"));
+    assert!(printed.contains("    op relu(t, alpha=0.1, clamp_max=inf) >> t;\n"));
+    assert!(printed.contains("    cache.reset state[.., -1];\n"));
+    assert!(printed.contains("loop inner (k in N..=0 step -1)"));
+}
+
+#[test]
+fn imported_graph_passes_validation() {
+    let graph = parse_graph(
+        r#"
+        dims { B: 1..=8; D = 64; }
+        dynamic { x: f32[B, D]; }
+        block entry {
+            assign t: f32[B, D];
+            op relu(x) >> t;
+            return;
+        }
+        "#,
+    );
+    validate(&graph).expect("valid graph");
+    let imported = from_json(&to_json(&graph)).expect("read json");
+    validate(&imported).expect("imported graph is valid");
+    assert_eq!(imported.to_string(), graph.to_string());
+}
+
+#[test]
+fn reports_json_errors_with_line_and_column() {
+    let err = from_json(
+        "{\n  \"schema\": \"openinfer-dsl/graph\",\n  \"version\": 2,\n  \"sections\": []\n}",
+    )
+    .err()
+    .expect("unsupported version");
+    assert_eq!(err.to_string(), "3:14: unsupported version 2 (expected 1)");
+
+    let err = from_json(
+        r#"{"schema": "openinfer-dsl/graph", "version": 1, "sections": [
+  {"section": "block", "name": "entry", "nodes": [{"node": "barrier", "when": 1}]}
+]}"#,
+    )
+    .err()
+    .expect("unknown field");
+    assert_eq!(err.to_string(), "2:79: unknown field `when`");
+
+    let err = from_json(
+        r#"{"schema": "openinfer-dsl/graph", "version": 1, "sections": [
+  {"section": "memory", "kind": "dynamic", "vars": [{"name": "x", "dtype": "f32", "dims": ["B+"]}]}
+]}"#,
+    )
+    .err()
+    .expect("bad dim");
+    assert_eq!(
+        err.to_string(),
+        "2:92: invalid dimension `B+`: unexpected end of input, \
+         expected identifier or integer for dimension expression"
+    );
+
+    let err = from_json("{\"schema\": \"openinfer-dsl/graph\", \"version\": 1,")
+        .err()
+        .expect("truncated");
+    assert_eq!(err.to_string(), "1:48: expected a string key");
+
+    let err = from_json(&"[".repeat(100_000)).err().expect("too deep");
+    assert_eq!(err.to_string(), "1:129: nesting deeper than 128 levels");
+}
@@ -0,0 +1,74 @@
+//! Parser, syntax tree and validation for the OpenInfer graph DSL.
+//!
+//! This crate is the front end of the `openinfer-dsl` macros, usable from
+//! build scripts, command-line tools and editors:
+//!
+//! - [`types`]: the syntax tree. [`GraphDsl`] implements `syn::parse::Parse`
+//!   and `Display`, which prints canonical DSL text.
+//! - [`validation::validate`]: the compile-time checks run before expansion.
+//! - [`check_source`]: parse and validate a file, with `path:line:column`
+//!   errors.
+//! - [`format`]: formatting of `.oinf` files and `graph!` bodies that keeps
+//!   comments and `repeat`.
+//! - [`dot::to_dot`]: Graphviz rendering for diagrams.
+//! - [`json`]: a versioned JSON form of the graph, written by
+//!   [`json::to_json`] and read back by [`json::from_json`].
+//!
+//! ## Example
+//! ```ignore
+//! let graph: openinfer_dsl_syntax::GraphDsl = syn::parse_str(source)?;
+//! openinfer_dsl_syntax::validation::validate(&graph)?;
+//! println!("{}", graph);
+//! ```
+#![warn(missing_docs)]
+
+use proc_macro2::TokenStream;
+
+mod attributes;
+mod diagnostics;
+pub mod dot;
+pub mod dtype;
+pub mod format;
+pub mod json;
+mod kw;
+mod parsers;
+mod printer;
+mod suggest;
+pub mod types;
+pub mod validation;
+
+pub use crate::types::GraphDsl;
+
+/// Parse and validate `source`, formatting every error as
+/// `path:line:column: message`.
+///
+/// Line and column numbers are only known when proc-macro2 uses its own
+/// lexer, i.e. outside of a procedural macro or after
+/// `proc_macro2::fallback::force()`.
+pub fn check_source(path: &str, source: &str) -> Result<(), Vec<String>> {
+    let tokens: TokenStream = source
+        .parse()
+        .map_err(|err: proc_macro2::LexError| vec![locate(path, err.span(), &err.to_string())])?;
+    let result = syn::parse2::<GraphDsl>(tokens).and_then(|graph| validation::validate(&graph));
+    result.map_err(|err| {
+        err.into_iter()
+            .map(|err| locate(path, err.span(), &err.to_string()))
+            .collect()
+    })
+}
+
+fn locate(path: &str, span: proc_macro2::Span, message: &str) -> String {
+    let start = span.start();
+    format!("{}:{}:{}: {}", path, start.line, start.column + 1, message)
+}
+
+#[cfg(test)]
+mod dot_tests;
+#[cfg(test)]
+mod format_tests;
+#[cfg(test)]
+mod json_tests;
+#[cfg(test)]
+mod parse_tests;
+#[cfg(test)]
+mod validation_tests;
@@ -0,0 +1,793 @@
+use crate::parsers::op::parse_op_attr_value;
+use crate::types::{
+    CacheIndexExpr, Dim, DimOp, DimValue, InitValue, MemoryKindToken, Node, OpAttrValue, RangeValue,
+    Section,
+};
+use crate::types::GraphDsl;
+use syn::parse::{Parser};
+use syn::parse_str;
+
+fn parse_graph(src: &str) -> GraphDsl {
+    parse_str::<GraphDsl>(src).expect("parse graph")
+}
+
+#[test]
+fn parses_full_graph_sections_and_nodes() {
+    let graph = parse_graph(
+        r#"
+        dynamic {
+            x: f32[B, D];
+            y: i32[4, B*D];
+        }
+
+        volatile {
+            tmp: f32[B, D] @init(0.0);
+            flag: bool @init(true);
+        }
+
+        constant {
+            w: f32[D, D] @ref("w.0") @pattern("gauss");
+            bias: f32[D] @pattern("zero");
+        }
+
+        persistent {
+            state(i, j): f32[D] @table @auto_dim(i, j) @fixed(i=2, j=3);
+        }
+
+        block entry {
+            assign t0: f32[B, D];
+            op add(x, w, alpha=0.5, clamp_max=6.0, axes=[1, 2], tag="relu", flag=true) >> t0;
+            cache.write t0 >> state[0, 1];
+            cache.read state[0, 1] >> t0;
+            cache.increment 2 state;
+            cache.decrement state;
+            cache.reset state[0, 1];
+            branch cond_block then_block else_block;
+            barrier;
+            dep after(block_a) before(block_b);
+            loop i (idx in 0..4) {
+                yield t0;
+                await t0;
+                transfer t0 >> tmp;
+                return;
+            }
+            return;
+        }
+        "#,
+    );
+
+    assert_eq!(graph.sections.len(), 5);
+    match &graph.sections[0] {
+        Section::Memory(section) => {
+            assert!(matches!(section.kind, MemoryKindToken::Dynamic));
+            assert_eq!(section.vars.len(), 2);
+        }
+        _ => panic!("expected memory section"),
+    }
+
+    let block = match &graph.sections[4] {
+        Section::Block(block) => block,
+        _ => panic!("expected block section"),
+    };
+    assert_eq!(block.nodes.len(), 12);
+    assert!(matches!(block.nodes[0], Node::Assign(_)));
+    assert!(matches!(block.nodes[1], Node::Op(_)));
+    assert!(matches!(block.nodes[2], Node::CacheWrite(_)));
+    assert!(matches!(block.nodes[3], Node::CacheRead(_)));
+    assert!(matches!(block.nodes[4], Node::CacheInc(_)));
+    assert!(matches!(block.nodes[5], Node::CacheDec(_)));
+    assert!(matches!(block.nodes[6], Node::CacheReset(_)));
+    assert!(matches!(block.nodes[7], Node::Branch(_)));
+    assert!(matches!(block.nodes[8], Node::Barrier));
+    assert!(matches!(block.nodes[9], Node::Dep(_)));
+    assert!(matches!(block.nodes[10], Node::Loop(_)));
+    assert!(matches!(block.nodes[11], Node::Return));
+}
+
+#[test]
+fn parses_dim_expressions() {
+    let graph = parse_graph(
+        r#"
+        dynamic {
+            x: f32[B*D, 4, K*2, B*H*D, S+1, (N+7)/8, ceil(N/8)];
+        }
+
+        block entry { return; }
+        "#,
+    );
+    let dims = match &graph.sections[0] {
+        Section::Memory(section) => &section.vars[0].dims,
+        _ => panic!("expected memory section"),
+    };
+    assert_eq!(dims.len(), 7);
+    assert!(matches!(dims[0], Dim::Binary { op: DimOp::Mul, .. }));
+    assert!(matches!(dims[1], Dim::Lit(_)));
+    assert!(matches!(dims[2], Dim::Binary { op: DimOp::Mul, .. }));
+    if let Dim::Binary { left, right, .. } = &dims[0] {
+        assert!(matches!(**left, Dim::Ident(_)));
+        assert!(matches!(**right, Dim::Ident(_)));
+    }
+    // `*` is left-associative: (B*H)*D.
+    if let Dim::Binary { left, right, .. } = &dims[3] {
+        assert!(matches!(**left, Dim::Binary { op: DimOp::Mul, .. }));
+        assert!(matches!(**right, Dim::Ident(_)));
+    }
+    assert!(matches!(dims[4], Dim::Binary { op: DimOp::Add, .. }));
+    if let Dim::Binary { op, left, .. } = &dims[5] {
+        assert!(*op == DimOp::Div);
+        assert!(matches!(**left, Dim::Binary { op: DimOp::Add, .. }));
+    }
+    assert!(matches!(dims[6], Dim::CeilDiv { .. }));
+}
+
+#[test]
+fn parses_dims_section() {
+    let graph = parse_graph(
+        r#"
+        dims {
+            B: 1..=64;
+            S: 1..4096;
+            D = 768;
+            H where D % H == 0, D % (2*H) == 0;
+        }
+        block entry { return; }
+        "#,
+    );
+    let Section::Dims(dims) = &graph.sections[0] else {
+        panic!("expected dims section");
+    };
+    assert_eq!(dims.dims.len(), 4);
+    assert!(matches!(
+        &dims.dims[0].value,
+        DimValue::Range { start, end, inclusive: true }
+            if start.base10_digits() == "1" && end.base10_digits() == "64"
+    ));
+    assert!(matches!(&dims.dims[1].value, DimValue::Range { inclusive: false, .. }));
+    assert!(matches!(&dims.dims[2].value, DimValue::Fixed(lit) if lit.base10_digits() == "768"));
+    assert!(matches!(dims.dims[3].value, DimValue::Free));
+    assert_eq!(dims.dims[3].constraints.len(), 2);
+    assert!(matches!(&dims.dims[3].constraints[0].dividend, Dim::Ident(ident) if ident == "D"));
+
+    let err = parse_str::<GraphDsl>("dims { H where D % H == 1; }")
+        .err()
+        .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("only divisibility constraints (`a % b == 0`) are supported"));
+}
+
+#[test]
+fn parses_init_values() {
+    let graph = parse_graph(
+        r#"
+        volatile {
+            a: f32 @init(1.25);
+            b: i32 @init(-3);
+            c: bool @init(false);
+        }
+        block entry { return; }
+        "#,
+    );
+    let vars = match &graph.sections[0] {
+        Section::Memory(section) => &section.vars,
+        _ => panic!("expected memory section"),
+    };
+    assert!(matches!(vars[0].init, Some(InitValue::Float { .. })));
+    assert!(matches!(vars[1].init, Some(InitValue::Int { negative: true, .. })));
+    assert!(matches!(vars[2].init, Some(InitValue::Bool { .. })));
+}
+
+#[test]
+fn parses_op_attr_values() {
+    let val = parse_op_attr_value.parse_str("3.5").unwrap();
+    assert!(matches!(val, OpAttrValue::Double(_)));
+    let val = parse_op_attr_value.parse_str("-7").unwrap();
+    assert!(matches!(val, OpAttrValue::Int(-7)));
+    let val = parse_op_attr_value.parse_str("true").unwrap();
+    assert!(matches!(val, OpAttrValue::Bool(true)));
+    let val = parse_op_attr_value.parse_str("\"tag\"").unwrap();
+    assert!(matches!(val, OpAttrValue::String(_)));
+    let val = parse_op_attr_value.parse_str("[1, -2, 3]").unwrap();
+    assert!(matches!(val, OpAttrValue::IntList(_)));
+}
+
+
+#[test]
+fn parses_cache_slices() {
+    let graph = parse_graph(
+        r#"
+        persistent {
+            table(i, j): f32 @table;
+            out: f32;
+        }
+
+        block entry {
+            cache.read table[1, .., 2.., ..3, 4..5] >> out;
+            return;
+        }
+        "#,
+    );
+    let block = match &graph.sections[1] {
+        Section::Block(block) => block,
+        _ => panic!("expected block"),
+    };
+    match &block.nodes[0] {
+        Node::CacheRead(node) => {
+            assert!(node.src.bracketed);
+            assert_eq!(node.src.indices.len(), 5);
+            assert!(matches!(node.src.indices[1], CacheIndexExpr::Slice { .. }));
+        }
+        _ => panic!("expected cache read"),
+    }
+}
+
+#[test]
+fn parse_errors_for_invalid_syntax() {
+    let err = parse_str::<GraphDsl>("foo { }")
+        .err()
+        .expect("expected parse error");
+    assert!(err.to_string().contains("expected dims, memory section, fn or block"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        volatile { x: f32 @init(true) @init(false); }
+        block entry { return; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err.to_string().contains("duplicate @init attribute"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32[B*]; }
+        block entry { return; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("expected identifier or integer for dimension expression"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32[B]; }
+        block entry { op add(x, alpha=1, y) >> x; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err.to_string().contains("positional args must come before settings"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        persistent { table(i): f32 @table; }
+        block entry { cache.read table[-i] >> x; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err.to_string().contains("unexpected '-' before identifier"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32; }
+        block entry { branch cond ok; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("branch expects condition and two target blocks"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32; }
+        block entry { loop i (idx in ..3) { return; } }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("expected identifier or integer for loop range"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        volatile { x: f32 @auto_dim(); }
+        block entry { return; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("@auto_dim requires at least one index"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32; }
+        block entry { cache.foo x; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err.to_string().contains("unsupported cache operation"));
+}
+
+#[test]
+fn parse_range_values() {
+    let graph = parse_graph(
+        r#"
+        dynamic { x: f32; }
+        block entry {
+            loop i (idx in 1..N) { return; }
+        }
+        "#,
+    );
+    let block = match &graph.sections[1] {
+        Section::Block(block) => block,
+        _ => panic!("expected block"),
+    };
+    match &block.nodes[0] {
+        Node::Loop(node) => {
+            assert!(matches!(node.start, RangeValue::Lit(_)));
+            assert!(matches!(node.end, RangeValue::Ident(_)));
+        }
+        _ => panic!("expected loop"),
+    }
+
+    let graph = parse_graph(
+        r#"
+        block entry {
+            loop a (i in 0..N step 2) { return; }
+            loop b (i in 0..=N) { return; }
+            loop c (i in N..=0 step -1) { return; }
+            loop d (i in 0..S-1) { return; }
+            loop e (i in 1..L*2) { return; }
+        }
+        "#,
+    );
+    let Section::Block(block) = &graph.sections[0] else {
+        panic!("expected block");
+    };
+    let loops: Vec<&crate::types::LoopNode> = block
+        .nodes
+        .iter()
+        .map(|node| match node {
+            Node::Loop(node) => node,
+            _ => panic!("expected loop"),
+        })
+        .collect();
+    assert_eq!(loops[0].step.as_ref().map(|s| s.base10_digits()), Some("2"));
+    assert!(!loops[0].inclusive);
+    assert!(loops[1].inclusive && loops[1].step.is_none());
+    assert!(matches!(loops[2].start, RangeValue::Ident(_)));
+    assert_eq!(loops[2].step.as_ref().map(|s| s.base10_digits()), Some("-1"));
+    assert!(matches!(
+        loops[3].end,
+        RangeValue::Expr(Dim::Binary { op: DimOp::Sub, .. })
+    ));
+    assert!(matches!(
+        loops[4].end,
+        RangeValue::Expr(Dim::Binary { op: DimOp::Mul, .. })
+    ));
+
+    let err = parse_str::<GraphDsl>("block entry { loop l (i in 0..N step 0) { return; } }")
+        .err()
+        .expect("expected parse error");
+    assert!(err.to_string().contains("loop step must not be zero"));
+}
+
+#[test]
+fn unrolls_literal_range_loops() {
+    let graph = parse_graph(
+        r#"
+        block entry {
+            loop layers (i in 0..3) @unroll {
+                assign h{i}: f32[D];
+                op add(x[i], h{i}) >> y;
+                cache.read state[i, 0..i] >> y;
+            }
+        }
+        "#,
+    );
+    let Section::Block(block) = &graph.sections[0] else {
+        panic!("expected block");
+    };
+    let Node::Loop(node) = &block.nodes[0] else {
+        panic!("expected loop");
+    };
+    assert!(node.unroll.is_some());
+    assert_eq!(node.body.len(), 9);
+    let Node::Assign(assign) = &node.body[3] else {
+        panic!("expected assign");
+    };
+    assert_eq!(assign.name, "h1");
+    let Node::Op(op) = &node.body[7] else {
+        panic!("expected op");
+    };
+    assert!(matches!(
+        &op.inputs[0].indices[0],
+        crate::types::IndexExpr::Lit(lit) if lit.base10_digits() == "2"
+    ));
+    assert_eq!(op.inputs[1].name, "h2");
+    let Node::CacheRead(read) = &node.body[5] else {
+        panic!("expected cache read");
+    };
+    assert!(matches!(
+        read.src.indices[1],
+        CacheIndexExpr::Slice { end: Some(crate::types::CacheIndexValue::Lit(1)), .. }
+    ));
+
+    let graph = parse_graph("block entry { loop l (i in 3..=0 step -2) @unroll { barrier; } }");
+    let Section::Block(block) = &graph.sections[0] else {
+        panic!("expected block");
+    };
+    let Node::Loop(node) = &block.nodes[0] else {
+        panic!("expected loop");
+    };
+    assert_eq!(node.body.len(), 2);
+
+    let err = parse_str::<GraphDsl>("block entry { loop l (i in 0..N) @unroll { barrier; } }")
+        .err()
+        .expect("expected parse error");
+    assert!(err.to_string().contains("@unroll loop l needs literal bounds, got N"));
+
+    let err = parse_str::<GraphDsl>("block entry { loop l (i in 0..4) @unrol { barrier; } }")
+        .err()
+        .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("unsupported loop attribute `unrol`; did you mean `unroll`?"));
+}
+
+#[test]
+fn expands_repeat_in_sections_and_blocks() {
+    let graph = parse_graph(
+        r#"
+        constant {
+            repeat i in 0..3 {
+                wq{i}: f32[D, D] @ref("layers.{i}.attn.q");
+            }
+            norm: f32[D];
+        }
+        block entry {
+            repeat i in 0..=1 {
+                op matmul(x, wq{i}) >> x;
+            }
+            repeat l in 0..2 {
+                repeat h in 0..2 { barrier; }
+            }
+            return;
+        }
+        "#,
+    );
+    let Section::Memory(mem) = &graph.sections[0] else {
+        panic!("expected memory section");
+    };
+    let names: Vec<String> = mem.vars.iter().map(|var| var.name.to_string()).collect();
+    assert_eq!(names, ["wq0", "wq1", "wq2", "norm"]);
+    assert_eq!(
+        mem.vars[2].ref_name.as_ref().map(|lit| lit.value()),
+        Some("layers.2.attn.q".to_string())
+    );
+    let Section::Block(block) = &graph.sections[1] else {
+        panic!("expected block");
+    };
+    assert_eq!(block.nodes.len(), 7);
+    let Node::Op(op) = &block.nodes[1] else {
+        panic!("expected op");
+    };
+    assert_eq!(op.inputs[1].name, "wq1");
+
+    let err = parse_str::<GraphDsl>("constant { repeat i in 0..L { w{i}: f32; } }")
+        .err()
+        .expect("expected parse error");
+    assert!(err.to_string().contains("repeat i needs literal bounds, got L"));
+}
+
+#[test]
+fn rejects_nested_templates_that_reuse_the_index() {
+    let err = parse_str::<GraphDsl>(
+        "block entry { repeat k in 0..2 {\n    repeat k in 0..2 { barrier; }\n} }",
+    )
+    .err()
+    .expect("expected parse error");
+    assert_eq!(
+        err.to_string(),
+        "index k is already bound by the enclosing repeat k"
+    );
+    assert_eq!(err.span().start().line, 2);
+    assert_eq!(err.span().start().column, 11);
+
+    let err = parse_str::<GraphDsl>(
+        "block entry { loop l (i in 0..2) @unroll { loop inner (i in 0..4) { barrier; } } }",
+    )
+    .err()
+    .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("index i is already bound by the enclosing @unroll loop l"));
+}
+
+#[test]
+fn inlines_fn_calls_with_renamed_temporaries() {
+    let graph = parse_graph(
+        r#"
+        fn mlp(x: f32[B, D], w: f32[D, D]) -> f32[B, D] {
+            assign h: f32[B, D];
+            op matmul(x, w) >> h;
+            assign out: f32[B, D];
+            op relu(h) >> out;
+            return out;
+        }
+        dynamic { a: f32[S, 768]; }
+        constant { w0: f32[768, 768]; w1: f32[768, 768]; }
+        volatile { y: f32[S, 768]; }
+        block entry {
+            call mlp(a, w0) >> y;
+            call mlp(y, w1) >> z;
+            return;
+        }
+        "#,
+    );
+    let Section::Block(block) = &graph.sections[4] else {
+        panic!("expected block");
+    };
+    let Node::Call(first) = &block.nodes[0] else {
+        panic!("expected call");
+    };
+    // The result temporary becomes the declared output.
+    assert_eq!(first.inlined.len(), 3);
+    let Node::Assign(h) = &first.inlined[0] else {
+        panic!("expected assign");
+    };
+    assert_eq!(h.name, "__mlp_0_h");
+    assert!(matches!(&h.dims[0], Dim::Ident(ident) if ident == "S"));
+    let Node::Op(matmul) = &first.inlined[1] else {
+        panic!("expected op");
+    };
+    assert_eq!(matmul.inputs[0].name, "a");
+    assert_eq!(matmul.inputs[1].name, "w0");
+    let Node::Op(relu) = &first.inlined[2] else {
+        panic!("expected op");
+    };
+    assert_eq!(relu.output, "y");
+
+    // An undeclared output is declared by the inlined body.
+    let Node::Call(second) = &block.nodes[1] else {
+        panic!("expected call");
+    };
+    assert_eq!(second.inlined.len(), 4);
+    assert!(matches!(&second.inlined[2], Node::Assign(assign) if assign.name == "z"));
+
+    let err = parse_str::<GraphDsl>("fn f(x: f32) -> f32 { barrier; }")
+        .err()
+        .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .contains("fn f must end with `return <value>;`"));
+}
+
+#[test]
+fn inlining_renames_only_variables_and_dims() {
+    let graph = parse_graph(
+        r#"
+        fn act(relu: f32[B, D], entry: f32[B, D]) -> f32[B, D] {
+            assign alpha: f32[B, D];
+            op relu(relu, alpha=0.5) >> alpha;
+            dep after(entry) before(other);
+            assign out: f32[B, D];
+            op add(alpha, entry) >> out;
+            return out;
+        }
+        dynamic { x: f32[S, 768]; }
+        block entry {
+            call act(x, x) >> y;
+            return;
+        }
+        block other { return; }
+        "#,
+    );
+    let Section::Block(block) = &graph.sections[2] else {
+        panic!("expected block");
+    };
+    let Node::Call(call) = &block.nodes[0] else {
+        panic!("expected call");
+    };
+    let Node::Assign(alpha) = &call.inlined[0] else {
+        panic!("expected assign");
+    };
+    assert_eq!(alpha.name, "__act_0_alpha");
+    assert!(matches!(&alpha.dims[0], Dim::Ident(ident) if ident == "S"));
+    assert!(matches!(&alpha.dims[1], Dim::Lit(lit) if lit.base10_digits() == "768"));
+    // The parameter `relu` does not rename the op, nor the temporary
+    // `alpha` the setting key.
+    let Node::Op(relu) = &call.inlined[1] else {
+        panic!("expected op");
+    };
+    assert_eq!(relu.name, "relu");
+    assert_eq!(relu.inputs[0].name, "x");
+    assert_eq!(relu.settings[0].name, "alpha");
+    assert_eq!(relu.output, "__act_0_alpha");
+    // Nor the parameter `entry` the block `dep` waits on.
+    let Node::Dep(dep) = &call.inlined[2] else {
+        panic!("expected dep");
+    };
+    assert_eq!(dep.after, "entry");
+    assert_eq!(dep.before, "other");
+    let Node::Op(add) = &call.inlined[4] else {
+        panic!("expected op");
+    };
+    assert_eq!(add.inputs[0].name, "__act_0_alpha");
+    assert_eq!(add.inputs[1].name, "x");
+    assert_eq!(add.output, "y");
+}
+
+#[test]
+fn parse_recovers_at_statement_and_block_boundaries() {
+    let err = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32[B*]; y: f32; z: f32 @bogus; }
+        block entry {
+            op add(x, alpha=1, y) >> x;
+            return;
+            cache.foo x;
+        }
+        oops { }
+        block other { loop l (i in ..3) { return; } }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+    assert_eq!(messages.len(), 6);
+    assert!(messages[0].contains("expected identifier or integer for dimension expression"));
+    assert!(messages[1].contains("unsupported attribute"));
+    assert!(messages[2].contains("positional args must come before settings"));
+    assert!(messages[3].contains("unsupported cache operation"));
+    assert!(messages[4].contains("expected dims, memory section, fn or block"));
+    assert!(messages[5].contains("expected identifier or integer for loop range"));
+}
+
+#[test]
+fn suggests_keywords_attributes_and_dtypes() {
+    let err = parse_str::<GraphDsl>("block entry { asign t: f32; }")
+        .err()
+        .expect("expected parse error");
+    assert!(err
+        .to_string()
+        .starts_with("unsupported node `asign`; did you mean `assign`? (expected one of: assign, op,"));
+
+    let err = parse_str::<GraphDsl>(
+        r#"
+        persistent { s(i): f32 @tabel; }
+        block entry { cache.wirte x >> s; }
+        "#,
+    )
+    .err()
+    .expect("expected parse error");
+    let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+    assert!(messages[0].starts_with("unsupported attribute `tabel`; did you mean `table`?"));
+    assert!(messages[1].starts_with("unsupported cache operation `wirte`; did you mean `write`?"));
+
+    let dtype = syn::Ident::new("f23", proc_macro2::Span::call_site());
+    let err = crate::dtype::check_dtype(&dtype).expect_err("unsupported dtype");
+    assert!(err
+        .to_string()
+        .starts_with("unsupported dtype `f23`; did you mean `f32`? (expected one of: i4, i8,"));
+}
+
+#[test]
+fn reports_file_errors_with_line_and_column() {
+    let source = "dynamic { x: f32[B]; }\nblock entry {\n    op ad(x) >> x;\n    op relu(hidden) >> x;\n}\n";
+    let errors =
+        crate::check_source("models/tiny.oinf", source).expect_err("invalid file");
+    assert_eq!(
+        errors,
+        [
+            "models/tiny.oinf:3:8: unknown op: ad; did you mean `add`?",
+            "models/tiny.oinf:4:13: unknown variable: hidden",
+        ]
+    );
+
+    let errors = crate::check_source("bad.oinf", "dynamic {\n  x f32;\n}\n")
+        .expect_err("parse error");
+    assert_eq!(errors, ["bad.oinf:2:5: expected `:`"]);
+
+    crate::check_source("ok.oinf", "block entry { return; }").expect("valid file");
+}
+
+const ROUND_TRIP_SRC: &str = r#"
+dims { B: 1..=64; S: 1..4096; D = 768; H where D % H == 0, (D+H)/2 % H == 0; }
+
+fn mlp(x: f32[B, D], w: f32[D, D]) -> f32[B, D] {
+    assign h: f32[B, D];
+    op matmul(x, w, acc=[f32]) >> h;
+    return h;
+}
+
+dynamic { x: f32[B, D]; y: i32[4, B*D]; }
+volatile { tmp: f32[B, D] @init(-0.5); n: i64 @init(3); flag: bool @init(true); }
+constant {
+    repeat i in 0..2 { w{i}: f32[D, D] @ref("layers.{i}.w") @pattern("gauss"); }
+    half: f16[ceil(S/8), (S-1)*2];
+}
+persistent {
+    state(i, j): f32[B, D] @table @auto_dim(j) @fixed(i=2);
+    steps: i64;
+}
+
+block entry {
+    assign t: f32[B, D];
+    op add(x, tmp) >> t;
+    op relu(t, alpha=0.1, clamp_max=inf) >> t;
+    op sum(t, axes=[-1, 0], keepdims=true) >> t;
+    cache.read state[0, ..j] >> t;
+    cache.write t >> state[i, 2..];
+    cache.increment 2 steps;
+    cache.decrement steps;
+    cache.reset state[.., -1];
+    transfer x >> tmp;
+    loop layers (l in 0..S-1 step 2) {
+        loop inner (k in N..=0 step -1) { barrier; }
+        dep after(x) before(t);
+    }
+    loop unrolled (u in 0..2) @unroll { op add(x, w{u}) >> x; }
+    call mlp(x, w0) >> x;
+    branch flag entry decode;
+    yield x, t;
+    await x;
+    branch decode;
+}
+
+block decode { return; }
+"#;
+
+#[test]
+fn prints_canonical_text_that_round_trips() {
+    let printed = parse_graph(ROUND_TRIP_SRC).to_string();
+    let reprinted = parse_graph(&printed).to_string();
+    assert_eq!(printed, reprinted);
+
+    assert!(printed.starts_with(
+        "dims {\n    B: 1..=64;\n    S: 1..4096;\n    D = 768;\n    H where D % H == 0, (D+H)/2 % H == 0;\n}\n\n"
+    ));
+    assert!(printed.contains(
+        "fn mlp(x: f32[B, D], w: f32[D, D]) -> f32[B, D] {\n    assign h: f32[B, D];\n    op matmul(x, w, acc=[f32]) >> h;\n    return h;\n}\n"
+    ));
+    assert!(printed.contains("    w1: f32[D, D] @ref(\"layers.1.w\") @pattern(\"gauss\");\n"));
+    assert!(printed.contains("    half: f16[ceil(S/8), (S-1)*2];\n"));
+    assert!(printed.contains("    state(i, j): f32[B, D] @table @auto_dim(j) @fixed(i=2);\n"));
+    assert!(printed.contains("    tmp: f32[B, D] @init(-0.5);\n"));
+    assert!(printed.contains("    op relu(t, alpha=0.1, clamp_max=inf) >> t;\n"));
+    assert!(printed.contains("    op sum(t, axes=[-1, 0], keepdims=true) >> t;\n"));
+    assert!(printed.contains("    cache.read state[0, ..j] >> t;\n"));
+    assert!(printed.contains("    cache.reset state[.., -1];\n"));
+    assert!(printed.contains("    cache.increment 2 steps;\n    cache.decrement steps;\n"));
+    assert!(printed.contains(
+        "    loop layers (l in 0..S-1 step 2) {\n        loop inner (k in N..=0 step -1) {\n            barrier;\n        }\n        dep after(x) before(t);\n    }\n"
+    ));
+    assert!(printed.contains("    op add(x, w0) >> x;\n    op add(x, w1) >> x;\n    call mlp(x, w0) >> x;\n"));
+    assert!(printed.ends_with("block decode {\n    return;\n}\n"));
+}
+
+#[test]
+fn prints_individual_nodes_and_dims() {
+    let dim = crate::parsers::dims::parse_dim_expr
+        .parse_str("a - (b - c) + (d * e) / (f * g)")
+        .expect("parse dim");
+    assert_eq!(dim.to_string(), "a-(b-c)+d*e/(f*g)");
+
+    let node: Node = parse_str("cache.write x[i, 0] >> kv[i, ..];").expect("parse node");
+    assert_eq!(node.to_string(), "cache.write x[i, 0] >> kv[i, ..];");
+
+    let node: Node = parse_str("loop l (i in 0..=N) { op add(a, b) >> c; }").expect("parse node");
+    assert_eq!(
+        node.to_string(),
+        "loop l (i in 0..=N) {\n    op add(a, b) >> c;\n}"
+    );
+}
@@ -0,0 +1,106 @@
+use syn::parse::{ParseStream, Result};
+use syn::{parenthesized, Ident, LitInt, Token};
+
+use crate::types::{Dim, DimOp};
+
+pub(crate) fn parse_dims(input: ParseStream) -> Result<Vec<Dim>> {
+    let mut dims = Vec::new();
+    if input.peek(syn::token::Bracket) {
+        let content;
+        syn::bracketed!(content in input);
+        while !content.is_empty() {
+            dims.push(parse_dim_expr(&content)?);
+            if content.peek(Token![,]) {
+                content.parse::<Token![,]>()?;
+            }
+        }
+    }
+    Ok(dims)
+}
+
+/// `expr := term (('+' | '-') term)*`
+pub(crate) fn parse_dim_expr(input: ParseStream) -> Result<Dim> {
+    let mut left = parse_dim_term(input)?;
+    loop {
+        let op = if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            DimOp::Add
+        } else if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            DimOp::Sub
+        } else {
+            return Ok(left);
+        };
+        let right = parse_dim_term(input)?;
+        left = Dim::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+    }
+}
+
+/// `term := factor (('*' | '/') factor)*`
+fn parse_dim_term(input: ParseStream) -> Result<Dim> {
+    let mut left = parse_dim_factor(input)?;
+    loop {
+        let op = if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            DimOp::Mul
+        } else if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            DimOp::Div
+        } else {
+            return Ok(left);
+        };
+        let right = parse_dim_factor(input)?;
+        left = Dim::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+    }
+}
+
+/// `factor := int | ident | '(' expr ')' | 'ceil' '(' expr '/' term ')'`
+fn parse_dim_factor(input: ParseStream) -> Result<Dim> {
+    if input.peek(LitInt) {
+        return Ok(Dim::Lit(input.parse()?));
+    }
+    if input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let inner = parse_dim_expr(&content)?;
+        if !content.is_empty() {
+            return Err(content.error("unexpected token in dimension expression"));
+        }
+        return Ok(inner);
+    }
+    if input.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        if ident == "ceil" && input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let inner = parse_dim_expr(&content)?;
+            if !content.is_empty() {
+                return Err(content.error("unexpected token in dimension expression"));
+            }
+            return match inner {
+                Dim::Binary {
+                    op: DimOp::Div,
+                    left,
+                    right,
+                } => Ok(Dim::CeilDiv {
+                    num: left,
+                    den: right,
+                }),
+                _ => Err(syn::Error::new(
+                    ident.span(),
+                    "ceil() expects a division, e.g. ceil(N / 8)",
+                )),
+            };
+        }
+        return Ok(Dim::Ident(ident));
+    }
+    Err(input.error("expected identifier or integer for dimension expression"))
+}
@@ -0,0 +1,501 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use syn::parse::{Parse, ParseStream, Parser, Result};
+use syn::{Ident, Token};
+
+use crate::diagnostics::Diagnostics;
+use crate::parsers::repeat::parse_repeated_items;
+use crate::suggest;
+use crate::types::{
+    AwaitNode, CacheAccess, CacheIndexExpr, CacheIndexValue, CallNode, Dim, FuncDef, GraphDsl,
+    IndexExpr, Node, OpAttrValue, RangeValue, Section, TransferNode, VarRef, YieldNode,
+};
+use crate::validation::shapes::{format_shape, sym_dim, sym_dim_with, Shape, SymDim};
+
+/// Item of a fn body: a node or the trailing `return value;`.
+enum FnItem {
+    Node(Node),
+    Result(Ident),
+}
+
+impl Parse for FnItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![return]) && input.peek2(Ident) {
+            input.parse::<Token![return]>()?;
+            let value = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Ok(FnItem::Result(value))
+        } else {
+            Ok(FnItem::Node(input.parse()?))
+        }
+    }
+}
+
+/// Parse the body of fn `name` into its nodes and the returned variable.
+pub(crate) fn parse_fn_body(
+    name: &Ident,
+) -> impl FnOnce(ParseStream) -> Result<(Vec<Node>, Ident)> + '_ {
+    move |input| {
+        let mut nodes = Vec::new();
+        let mut result: Option<Ident> = None;
+        for item in parse_repeated_items::<FnItem>(input)? {
+            if let Some(value) = &result {
+                return Err(syn::Error::new(
+                    value.span(),
+                    format!(
+                        "`return {}` must be the last statement of fn {}",
+                        value, name
+                    ),
+                ));
+            }
+            match item {
+                FnItem::Node(node) => nodes.push(node),
+                FnItem::Result(value) => result = Some(value),
+            }
+        }
+        match result {
+            Some(value) => Ok((nodes, value)),
+            None => Err(syn::Error::new(
+                name.span(),
+                format!("fn {} must end with `return <value>;`", name),
+            )),
+        }
+    }
+}
+
+/// Inline every `call` in the graph's blocks.
+///
+/// Each call site gets a fresh copy of the fn body in which parameters are
+/// replaced by the arguments, dims of the signature by the caller's dims,
+/// `assign` temporaries by `__<fn>_<n>_<name>` and the returned value by the
+/// call's output. Arguments and output are checked against the signature.
+pub(crate) fn inline_calls(graph: &mut GraphDsl) -> Result<()> {
+    let mut inliner = Inliner {
+        funcs: HashMap::new(),
+        vars: HashMap::new(),
+        next_id: 0,
+        stack: Vec::new(),
+        diagnostics: Diagnostics::default(),
+    };
+    for section in &graph.sections {
+        match section {
+            Section::Func(func) => match inliner.funcs.entry(func.name.to_string()) {
+                Entry::Occupied(entry) => inliner.diagnostics.push(syn::Error::new(
+                    func.name.span(),
+                    format!("duplicate fn: {}", entry.key()),
+                )),
+                Entry::Vacant(entry) => {
+                    entry.insert(func.clone());
+                }
+            },
+            Section::Memory(mem) => {
+                for var in &mem.vars {
+                    inliner.declare(&var.name, &var.dtype, &var.dims);
+                }
+            }
+            Section::Block(block) => inliner.declare_assigns(&block.nodes),
+            Section::Dims(_) => {}
+        }
+    }
+    for section in &mut graph.sections {
+        if let Section::Block(block) = section {
+            inliner.inline_nodes(&mut block.nodes);
+        }
+    }
+    inliner.diagnostics.finish()
+}
+
+struct Inliner {
+    funcs: HashMap<String, FuncDef>,
+    /// Declared dtype and dims of every variable, for signature checks.
+    vars: HashMap<String, (Ident, Vec<Dim>)>,
+    next_id: usize,
+    /// Fns currently being inlined, to reject recursion.
+    stack: Vec<String>,
+    diagnostics: Diagnostics,
+}
+
+impl Inliner {
+    fn declare(&mut self, name: &Ident, dtype: &Ident, dims: &[Dim]) {
+        self.vars
+            .entry(name.to_string())
+            .or_insert_with(|| (dtype.clone(), dims.to_vec()));
+    }
+
+    fn declare_assigns(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Assign(assign) => self.declare(&assign.name, &assign.dtype, &assign.dims),
+                Node::Loop(loop_node) => self.declare_assigns(&loop_node.body),
+                _ => {}
+            }
+        }
+    }
+
+    fn inline_nodes(&mut self, nodes: &mut [Node]) {
+        for node in nodes {
+            match node {
+                Node::Call(call) => {
+                    let result = self.inline_call(call);
+                    if let Some(inlined) = self.diagnostics.record(result) {
+                        call.inlined = inlined;
+                    }
+                }
+                Node::Loop(loop_node) => self.inline_nodes(&mut loop_node.body),
+                _ => {}
+            }
+        }
+    }
+
+    fn inline_call(&mut self, call: &CallNode) -> Result<Vec<Node>> {
+        let key = call.name.to_string();
+        let Some(func) = self.funcs.get(&key).cloned() else {
+            return Err(syn::Error::new(
+                call.name.span(),
+                format!(
+                    "unknown fn: {}{}",
+                    key,
+                    suggest::did_you_mean(&key, self.funcs.keys().map(String::as_str))
+                ),
+            ));
+        };
+        if self.stack.contains(&key) {
+            return Err(syn::Error::new(
+                call.name.span(),
+                format!("recursive call to fn {}", key),
+            ));
+        }
+        if call.args.len() != func.params.len() {
+            return Err(syn::Error::new(
+                call.name.span(),
+                format!(
+                    "fn {} expects {} argument{}, got {}",
+                    key,
+                    func.params.len(),
+                    if func.params.len() == 1 { "" } else { "s" },
+                    call.args.len()
+                ),
+            ));
+        }
+        let bindings = self.check_signature(call, &func)?;
+
+        let (mut nodes, result) = parse_fn_body(&func.name).parse2(func.body.clone())?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut renames = Renames {
+            vars: HashMap::new(),
+            dims: bindings,
+        };
+        for (param, arg) in func.params.iter().zip(&call.args) {
+            renames.vars.insert(param.name.to_string(), arg.clone());
+        }
+        let mut temporaries = Vec::new();
+        collect_assigns(&nodes, &mut temporaries);
+        let result_is_temporary = temporaries.contains(&result.to_string());
+        let output_declared = self.vars.contains_key(&call.output.to_string());
+        for name in &temporaries {
+            let renamed = if result == name {
+                call.output.clone()
+            } else {
+                Ident::new(&format!("__{}_{}_{}", key, id, name), call.name.span())
+            };
+            renames.vars.insert(name.clone(), renamed);
+        }
+
+        renames.nodes(&mut nodes);
+        let result = renames.var(&result);
+        if result_is_temporary {
+            // An output the caller did not declare is declared by the fn.
+            if output_declared {
+                remove_assign(&mut nodes, &call.output);
+            }
+        } else {
+            nodes.push(Node::Transfer(TransferNode {
+                src: VarRef {
+                    name: result,
+                    indices: Vec::new(),
+                },
+                dst: VarRef {
+                    name: call.output.clone(),
+                    indices: Vec::new(),
+                },
+            }));
+        }
+        self.declare_assigns(&nodes);
+        self.stack.push(key);
+        self.inline_nodes(&mut nodes);
+        self.stack.pop();
+        Ok(nodes)
+    }
+
+    /// Check arguments and output against the signature, binding each
+    /// signature dim that is a bare symbol to the caller's dim.
+    fn check_signature(&self, call: &CallNode, func: &FuncDef) -> Result<HashMap<String, Dim>> {
+        let mut diagnostics = Diagnostics::default();
+        let mut bindings: HashMap<String, Dim> = HashMap::new();
+        for (param, arg) in func.params.iter().zip(&call.args) {
+            let Some((dtype, dims)) = self.vars.get(&arg.to_string()) else {
+                continue;
+            };
+            if *dtype != param.dtype {
+                diagnostics.push(syn::Error::new(
+                    arg.span(),
+                    format!(
+                        "argument {} of call {} is {} but parameter {} is {}",
+                        arg, call.name, dtype, param.name, param.dtype
+                    ),
+                ));
+            }
+            if dims.len() == param.dims.len() {
+                for (expected, actual) in param.dims.iter().zip(dims) {
+                    if let Dim::Ident(symbol) = expected {
+                        bindings
+                            .entry(symbol.to_string())
+                            .or_insert_with(|| actual.clone());
+                    }
+                }
+            }
+            diagnostics.record(check_shape(
+                &param.dims,
+                dims,
+                &bindings,
+                |expected, actual| {
+                    format!(
+                        "argument {} of call {} has shape {} but parameter {} expects {}",
+                        arg, call.name, actual, param.name, expected
+                    )
+                },
+                arg,
+            ));
+        }
+        if let Some((dtype, dims)) = self.vars.get(&call.output.to_string()) {
+            if *dtype != func.ret_dtype {
+                diagnostics.push(syn::Error::new(
+                    call.output.span(),
+                    format!(
+                        "call {} returns {} but {} is {}",
+                        call.name, func.ret_dtype, call.output, dtype
+                    ),
+                ));
+            }
+            diagnostics.record(check_shape(
+                &func.ret_dims,
+                dims,
+                &bindings,
+                |expected, actual| {
+                    format!(
+                        "call {} returns {} but {} is declared {}",
+                        call.name, expected, call.output, actual
+                    )
+                },
+                &call.output,
+            ));
+        }
+        diagnostics.finish()?;
+        Ok(bindings)
+    }
+}
+
+fn check_shape(
+    expected: &[Dim],
+    actual: &[Dim],
+    bindings: &HashMap<String, Dim>,
+    message: impl FnOnce(String, String) -> String,
+    at: &Ident,
+) -> Result<()> {
+    let bound = |ident: &Ident| match bindings.get(&ident.to_string()) {
+        Some(dim) => sym_dim(dim),
+        None => Ok(SymDim::symbol(&ident.to_string())),
+    };
+    let expected: Shape = expected
+        .iter()
+        .map(|dim| sym_dim_with(dim, &bound))
+        .collect::<Result<_>>()?;
+    let actual: Shape = actual.iter().map(sym_dim).collect::<Result<_>>()?;
+    if expected == actual {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        at.span(),
+        message(format_shape(&expected), format_shape(&actual)),
+    ))
+}
+
+fn collect_assigns(nodes: &[Node], out: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            Node::Assign(assign) => out.push(assign.name.to_string()),
+            Node::Loop(loop_node) => collect_assigns(&loop_node.body, out),
+            _ => {}
+        }
+    }
+}
+
+fn remove_assign(nodes: &mut Vec<Node>, name: &Ident) {
+    nodes.retain(|node| !matches!(node, Node::Assign(assign) if assign.name == *name));
+    for node in nodes {
+        if let Node::Loop(loop_node) = node {
+            remove_assign(&mut loop_node.body, name);
+        }
+    }
+}
+
+/// Renames applied to an inlined fn body. Only identifiers in variable and
+/// dim positions are renamed; op names, setting keys, block and loop names
+/// are left alone even when they share a name with a parameter.
+#[derive(Clone)]
+struct Renames {
+    /// Parameters, temporaries and the result, by their name in the fn.
+    vars: HashMap<String, Ident>,
+    /// Signature dims bound to the caller's dims.
+    dims: HashMap<String, Dim>,
+}
+
+impl Renames {
+    fn var(&self, ident: &Ident) -> Ident {
+        match self.vars.get(&ident.to_string()) {
+            Some(renamed) => Ident::new(&renamed.to_string(), ident.span()),
+            None => ident.clone(),
+        }
+    }
+
+    fn rename(&self, ident: &mut Ident) {
+        *ident = self.var(ident);
+    }
+
+    fn nodes(&self, nodes: &mut [Node]) {
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&self, node: &mut Node) {
+        match node {
+            Node::Assign(assign) => {
+                self.rename(&mut assign.name);
+                for dim in &mut assign.dims {
+                    self.dim(dim);
+                }
+            }
+            Node::Op(op) => {
+                for input in &mut op.inputs {
+                    self.var_ref(input);
+                }
+                for setting in &mut op.settings {
+                    match &mut setting.value {
+                        OpAttrValue::Var(ident) => self.rename(ident),
+                        OpAttrValue::VarList(idents) => {
+                            idents.iter_mut().for_each(|ident| self.rename(ident))
+                        }
+                        _ => {}
+                    }
+                }
+                self.rename(&mut op.output);
+            }
+            Node::Branch(branch) => {
+                if let Some(cond) = &mut branch.cond {
+                    self.rename(cond);
+                }
+            }
+            Node::CacheRead(read) => {
+                self.cache_access(&mut read.src);
+                self.var_ref(&mut read.dst);
+            }
+            Node::CacheWrite(write) => {
+                self.var_ref(&mut write.src);
+                self.cache_access(&mut write.dst);
+            }
+            Node::CacheInc(inc) => self.rename(&mut inc.target),
+            Node::CacheDec(dec) => self.rename(&mut dec.target),
+            Node::CacheReset(reset) => self.cache_access(&mut reset.target),
+            Node::Transfer(transfer) => {
+                self.var_ref(&mut transfer.src);
+                self.var_ref(&mut transfer.dst);
+            }
+            Node::Loop(loop_node) => {
+                self.range_value(&mut loop_node.start);
+                self.range_value(&mut loop_node.end);
+                // The loop index shadows any parameter or dim of that name.
+                let index = loop_node.index.to_string();
+                if self.vars.contains_key(&index) || self.dims.contains_key(&index) {
+                    let mut inner = self.clone();
+                    inner.vars.remove(&index);
+                    inner.dims.remove(&index);
+                    inner.nodes(&mut loop_node.body);
+                } else {
+                    self.nodes(&mut loop_node.body);
+                }
+            }
+            Node::Call(call) => {
+                call.args.iter_mut().for_each(|arg| self.rename(arg));
+                self.rename(&mut call.output);
+            }
+            Node::Yield(YieldNode { vars }) | Node::Await(AwaitNode { vars }) => {
+                vars.iter_mut().for_each(|var| self.rename(var))
+            }
+            // `dep` orders blocks, not variables.
+            Node::Dep(_) | Node::Barrier | Node::Return => {}
+        }
+    }
+
+    fn var_ref(&self, var: &mut VarRef) {
+        self.rename(&mut var.name);
+        for index in &mut var.indices {
+            if let IndexExpr::Ident(ident) = index {
+                self.rename(ident);
+            }
+        }
+    }
+
+    fn cache_access(&self, access: &mut CacheAccess) {
+        self.rename(&mut access.name);
+        for index in &mut access.indices {
+            match index {
+                CacheIndexExpr::Single(value) => self.cache_index(value),
+                CacheIndexExpr::Slice { start, end } => {
+                    start.iter_mut().for_each(|value| self.cache_index(value));
+                    end.iter_mut().for_each(|value| self.cache_index(value));
+                }
+            }
+        }
+    }
+
+    fn cache_index(&self, value: &mut CacheIndexValue) {
+        if let CacheIndexValue::Ident(ident) = value {
+            self.rename(ident);
+        }
+    }
+
+    fn dim(&self, dim: &mut Dim) {
+        match dim {
+            Dim::Ident(ident) => {
+                if let Some(bound) = self.dims.get(&ident.to_string()) {
+                    *dim = bound.clone();
+                }
+            }
+            Dim::Lit(_) => {}
+            Dim::Binary { left, right, .. } => {
+                self.dim(left);
+                self.dim(right);
+            }
+            Dim::CeilDiv { num, den } => {
+                self.dim(num);
+                self.dim(den);
+            }
+        }
+    }
+
+    fn range_value(&self, value: &mut RangeValue) {
+        match value {
+            RangeValue::Ident(ident) => match self.dims.get(&ident.to_string()) {
+                Some(Dim::Ident(bound)) => *ident = bound.clone(),
+                Some(Dim::Lit(lit)) => *value = RangeValue::Lit(lit.clone()),
+                Some(bound) => *value = RangeValue::Expr(bound.clone()),
+                None => self.rename(ident),
+            },
+            RangeValue::Lit(_) => {}
+            RangeValue::Expr(dim) => self.dim(dim),
+        }
+    }
+}
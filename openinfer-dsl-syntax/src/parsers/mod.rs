@@ -1,7 +1,11 @@
 pub(crate) mod cache;
 pub(crate) mod dims;
+pub(crate) mod inline;
 pub(crate) mod node;
 pub(crate) mod op;
 pub(crate) mod range;
+pub(crate) mod recover;
+pub(crate) mod repeat;
 pub(crate) mod sections;
+pub(crate) mod template;
 pub(crate) mod var;
@@ -1,15 +1,20 @@
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream, Result};
-use syn::{parenthesized, Token};
+use syn::{parenthesized, Ident, Token};
 
 use crate::kw;
 use crate::parsers::cache::{parse_cache_access, parse_cache_amount};
 use crate::parsers::dims::parse_dims;
 use crate::parsers::op::parse_op_arg;
-use crate::parsers::range::parse_range_value;
+use crate::parsers::range::{
+    literal_range_values, parse_range_limits, parse_range_step, parse_range_value,
+};
+use crate::parsers::repeat::{self, parse_repeated_items};
 use crate::parsers::var::parse_var_ref;
+use crate::suggest;
 use crate::types::{
     AssignNode, AwaitNode, BranchNode, CacheDecNode, CacheIncNode, CacheReadNode, CacheResetNode,
-    CacheWriteNode, DepNode, LoopNode, Node, OpArg, OpNode, TransferNode, YieldNode,
+    CacheWriteNode, CallNode, DepNode, LoopNode, Node, OpArg, OpNode, TransferNode, YieldNode,
 };
 
 impl Parse for Node {
@@ -49,7 +54,11 @@ impl Parse for Node {
                 input.parse::<Token![;]>()?;
                 Ok(Node::CacheReset(CacheResetNode { target }))
             } else {
-                Err(input.error("unsupported cache operation"))
+                Err(input.error(suggest::unsupported(
+                    "cache operation",
+                    peek_word(input).as_deref(),
+                    CACHE_OPS,
+                )))
             }
         } else if input.peek(kw::assign) {
             input.parse::<kw::assign>()?;
@@ -136,25 +145,36 @@ impl Parse for Node {
             Ok(Node::Dep(DepNode { after, before }))
         } else if input.peek(Token![loop]) {
             input.parse::<Token![loop]>()?;
-            let name = input.parse()?;
+            let name: Ident = input.parse()?;
             let content;
             parenthesized!(content in input);
-            let index = content.parse()?;
+            let index: Ident = content.parse()?;
             content.parse::<Token![in]>()?;
             let start = parse_range_value(&content)?;
-            content.parse::<Token![..]>()?;
+            let inclusive = parse_range_limits(&content)?;
             let end = parse_range_value(&content)?;
+            let step = parse_range_step(&content)?;
+            let unroll_attr = parse_loop_attr(input)?;
             let body_content;
             syn::braced!(body_content in input);
-            let mut body = Vec::new();
-            while !body_content.is_empty() {
-                body.push(body_content.parse()?);
-            }
+            let (unroll, body) = if unroll_attr {
+                let tokens: proc_macro2::TokenStream = body_content.parse()?;
+                let what = format!("@unroll loop {}", name);
+                let values =
+                    literal_range_values(&what, &name, &start, &end, inclusive, step.as_ref())?;
+                let body = repeat::expand(&tokens, &index, &values, &what)?;
+                (Some(tokens), body)
+            } else {
+                (None, parse_repeated_items(&body_content)?)
+            };
             Ok(Node::Loop(LoopNode {
                 name,
                 index,
                 start,
                 end,
+                inclusive,
+                step,
+                unroll,
                 body,
             }))
         } else if input.peek(Token![yield]) {
@@ -196,12 +216,68 @@ impl Parse for Node {
             let dst = parse_var_ref(input)?;
             input.parse::<Token![;]>()?;
             Ok(Node::Transfer(TransferNode { src, dst }))
+        } else if input.peek(kw::call) {
+            input.parse::<kw::call>()?;
+            let name = input.parse()?;
+            let content;
+            parenthesized!(content in input);
+            let args = content
+                .parse_terminated(Ident::parse, Token![,])?
+                .into_iter()
+                .collect();
+            input.parse::<Token![>>]>()?;
+            let output = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Ok(Node::Call(CallNode {
+                name,
+                args,
+                output,
+                inlined: Vec::new(),
+            }))
         } else if input.peek(Token![return]) {
             input.parse::<Token![return]>()?;
             input.parse::<Token![;]>()?;
             Ok(Node::Return)
         } else {
-            Err(input.error("unsupported node"))
+            Err(input.error(suggest::unsupported(
+                "node",
+                peek_word(input).as_deref(),
+                NODE_KEYWORDS,
+            )))
         }
     }
 }
+
+const NODE_KEYWORDS: &[&str] = &[
+    "assign", "op", "branch", "barrier", "dep", "loop", "call", "yield", "await", "transfer",
+    "cache", "repeat", "return",
+];
+
+const CACHE_OPS: &[&str] = &["read", "write", "increment", "decrement", "reset"];
+
+const LOOP_ATTRIBUTES: &[&str] = &["unroll"];
+
+/// Optional `@unroll` between a loop header and its body.
+fn parse_loop_attr(input: ParseStream) -> Result<bool> {
+    if !input.peek(Token![@]) {
+        return Ok(false);
+    }
+    input.parse::<Token![@]>()?;
+    let attr = input.call(Ident::parse_any)?;
+    if attr != "unroll" {
+        return Err(syn::Error::new(
+            attr.span(),
+            suggest::unsupported("loop attribute", Some(&attr.to_string()), LOOP_ATTRIBUTES),
+        ));
+    }
+    Ok(true)
+}
+
+/// The next token as a word (keywords included), for diagnostics.
+pub(crate) fn peek_word(input: ParseStream) -> Option<String> {
+    input
+        .fork()
+        .call(Ident::parse_any)
+        .ok()
+        .map(|ident| ident.to_string())
+}
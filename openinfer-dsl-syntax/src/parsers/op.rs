@@ -1,6 +1,7 @@
 use syn::parse::{ParseStream, Result};
 use syn::{Ident, LitFloat, LitInt, LitStr, Token};
 
+use crate::dtype::DTYPES;
 use crate::parsers::var::parse_indices;
 use crate::types::{OpArg, OpAttrValue, OpSetting, VarRef};
 
@@ -22,11 +23,7 @@ pub(crate) fn parse_op_arg(input: ParseStream) -> Result<OpArg> {
 }
 
 fn is_dtype_ident(name: &str) -> bool {
-    matches!(
-        name,
-        "i4" | "i8" | "i16" | "i32" | "i64" | "u4" | "u8" | "u16" | "u32" | "u64"
-            | "f8" | "bf16" | "f16" | "f32" | "f64" | "bool"
-    )
+    DTYPES.contains(&name)
 }
 
 pub(crate) fn parse_op_attr_value(input: ParseStream) -> Result<OpAttrValue> {
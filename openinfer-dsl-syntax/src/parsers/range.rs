@@ -0,0 +1,103 @@
+use syn::parse::{ParseStream, Result};
+use syn::{Ident, LitInt, Token};
+
+use crate::kw;
+use crate::parsers::dims::parse_dim_expr;
+use crate::types::{Dim, RangeValue};
+use crate::validation::loops::range_constant;
+use crate::validation::shapes::sym_dim;
+
+/// A loop bound: a literal, an identifier or a dimension expression such as
+/// `S-1` or `L*2`.
+pub(crate) fn parse_range_value(input: ParseStream) -> Result<RangeValue> {
+    if !(input.peek(LitInt) || input.peek(Ident) || input.peek(syn::token::Paren)) {
+        return Err(input.error("expected identifier or integer for loop range"));
+    }
+    Ok(match parse_dim_expr(input)? {
+        Dim::Ident(ident) => RangeValue::Ident(ident),
+        Dim::Lit(lit) => RangeValue::Lit(lit),
+        expr => RangeValue::Expr(expr),
+    })
+}
+
+/// `..` or `..=`; returns whether the end bound is inclusive.
+pub(crate) fn parse_range_limits(input: ParseStream) -> Result<bool> {
+    if input.peek(Token![..=]) {
+        input.parse::<Token![..=]>()?;
+        Ok(true)
+    } else {
+        input.parse::<Token![..]>()?;
+        Ok(false)
+    }
+}
+
+/// Optional `step k`, where `k` is a non-zero integer; negative steps count
+/// down from `start` towards `end`.
+pub(crate) fn parse_range_step(input: ParseStream) -> Result<Option<LitInt>> {
+    if !input.peek(kw::step) {
+        return Ok(None);
+    }
+    input.parse::<kw::step>()?;
+    let step: LitInt = input.parse()?;
+    if step.base10_parse::<i64>()? == 0 {
+        return Err(syn::Error::new(step.span(), "loop step must not be zero"));
+    }
+    Ok(Some(step))
+}
+
+fn bound_text(value: &RangeValue) -> String {
+    match value {
+        RangeValue::Ident(ident) => ident.to_string(),
+        RangeValue::Lit(lit) => lit.to_string(),
+        RangeValue::Expr(dim) => sym_dim(dim).map(|dim| dim.to_string()).unwrap_or_default(),
+    }
+}
+
+/// Upper bound on the iterations an `@unroll` loop or `repeat` may expand to.
+const MAX_EXPANSION: usize = 1024;
+
+/// Every index value of an `@unroll` loop or `repeat`, whose bounds must be
+/// known at compile time. Ranges that step away from their end yield no
+/// values; the validation pass reports those for loops.
+pub(crate) fn literal_range_values(
+    what: &str,
+    name: &Ident,
+    start: &RangeValue,
+    end: &RangeValue,
+    inclusive: bool,
+    step: Option<&LitInt>,
+) -> Result<Vec<i64>> {
+    let constant = |value: &RangeValue| {
+        range_constant(value).ok_or_else(|| {
+            syn::Error::new(
+                value.span(),
+                format!("{} needs literal bounds, got {}", what, bound_text(value)),
+            )
+        })
+    };
+    let start = constant(start)?;
+    let end = constant(end)?;
+    let step = match step {
+        Some(step) => step.base10_parse::<i64>()?,
+        None => 1,
+    };
+    let in_range = |value: i64| match (step > 0, inclusive) {
+        (true, true) => value <= end,
+        (true, false) => value < end,
+        (false, true) => value >= end,
+        (false, false) => value > end,
+    };
+    let mut values = Vec::new();
+    let mut value = start;
+    while in_range(value) {
+        if values.len() == MAX_EXPANSION {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("{} expands to more than {} iterations", what, MAX_EXPANSION),
+            ));
+        }
+        values.push(value);
+        value += step;
+    }
+    Ok(values)
+}
@@ -0,0 +1,45 @@
+use proc_macro2::{Delimiter, TokenTree};
+use syn::parse::discouraged::Speculative;
+use syn::parse::{Parse, ParseStream, Result};
+
+use crate::diagnostics::Diagnostics;
+
+/// Parse `T` items until `input` is exhausted.
+///
+/// An item that fails to parse is reported and skipped up to the end of its
+/// statement (`;`) or braced body, so the remaining items are still checked
+/// and every error is returned together.
+pub(crate) fn parse_items<T: Parse>(input: ParseStream) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    let mut diagnostics = Diagnostics::default();
+    while !input.is_empty() {
+        let fork = input.fork();
+        match fork.parse::<T>() {
+            Ok(item) => {
+                input.advance_to(&fork);
+                items.push(item);
+            }
+            Err(err) => {
+                diagnostics.push(err);
+                skip_item(input)?;
+            }
+        }
+    }
+    diagnostics.finish()?;
+    Ok(items)
+}
+
+fn skip_item(input: ParseStream) -> Result<()> {
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        while let Some((tt, next)) = rest.token_tree() {
+            rest = next;
+            match tt {
+                TokenTree::Punct(punct) if punct.as_char() == ';' => break,
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => break,
+                _ => {}
+            }
+        }
+        Ok(((), rest))
+    })
+}
@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream, Parser, Result};
+use syn::{braced, Ident, Token};
+
+use crate::kw;
+use crate::parsers::range::{
+    literal_range_values, parse_range_limits, parse_range_step, parse_range_value,
+};
+use crate::parsers::recover::parse_items;
+use crate::parsers::template;
+
+/// An item of a memory section or block, or a `repeat` that expands to
+/// several of them.
+enum Repeated<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T: Parse> Parse for Repeated<T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if !input.peek(kw::repeat) {
+            return Ok(Repeated::One(input.parse()?));
+        }
+        input.parse::<kw::repeat>()?;
+        let index: Ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let start = parse_range_value(input)?;
+        let inclusive = parse_range_limits(input)?;
+        let end = parse_range_value(input)?;
+        let step = parse_range_step(input)?;
+        let content;
+        braced!(content in input);
+        let tokens: TokenStream = content.parse()?;
+        let what = format!("repeat {}", index);
+        let values = literal_range_values(&what, &index, &start, &end, inclusive, step.as_ref())?;
+        expand(&tokens, &index, &values, &what).map(Repeated::Many)
+    }
+}
+
+/// Parse `T` items, expanding `repeat i in 0..N { ... }` in place.
+pub(crate) fn parse_repeated_items<T: Parse>(input: ParseStream) -> Result<Vec<T>> {
+    let items = parse_items::<Repeated<T>>(input)?;
+    let mut out = Vec::new();
+    for item in items {
+        match item {
+            Repeated::One(item) => out.push(item),
+            Repeated::Many(items) => out.extend(items),
+        }
+    }
+    Ok(out)
+}
+
+/// Parse `tokens` once per value of `index`, with the value substituted.
+/// `what` names the construct being expanded, for errors.
+pub(crate) fn expand<T: Parse>(
+    tokens: &TokenStream,
+    index: &Ident,
+    values: &[i64],
+    what: &str,
+) -> Result<Vec<T>> {
+    let index = index.to_string();
+    template::check_shadowing(tokens, &index, what)?;
+    let mut out = Vec::new();
+    for value in values {
+        let expanded = template::substitute(tokens.clone(), &index, *value);
+        out.extend(parse_repeated_items::<T>.parse2(expanded)?);
+    }
+    Ok(out)
+}
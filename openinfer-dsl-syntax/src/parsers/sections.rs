@@ -0,0 +1,220 @@
+use syn::parse::{Parse, ParseStream, Parser, Result};
+use syn::{braced, parenthesized, Ident, LitInt, Token};
+
+use crate::attributes;
+use crate::kw;
+use crate::parsers::dims::{parse_dim_expr, parse_dims};
+use crate::parsers::inline::{inline_calls, parse_fn_body};
+use crate::parsers::recover::parse_items;
+use crate::parsers::repeat::parse_repeated_items;
+use crate::types::{
+    BlockSection, DimConstraint, DimDecl, DimValue, DimsSection, FuncDef, FuncParam, GraphDsl,
+    MemoryKindToken, MemorySection, Section, VarDecl,
+};
+
+impl Parse for GraphDsl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let sections = parse_items(input)?;
+        let mut graph = Self { sections };
+        inline_calls(&mut graph)?;
+        Ok(graph)
+    }
+}
+
+impl Parse for Section {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::dims) {
+            Ok(Section::Dims(input.parse()?))
+        } else if input.peek(kw::dynamic)
+            || input.peek(kw::volatile)
+            || input.peek(kw::constant)
+            || input.peek(kw::persistent)
+        {
+            Ok(Section::Memory(input.parse()?))
+        } else if input.peek(kw::block) {
+            Ok(Section::Block(input.parse()?))
+        } else if input.peek(Token![fn]) {
+            Ok(Section::Func(input.parse()?))
+        } else {
+            Err(input.error("expected dims, memory section, fn or block"))
+        }
+    }
+}
+
+impl Parse for DimsSection {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::dims>()?;
+        let content;
+        braced!(content in input);
+        let dims = parse_items(&content)?;
+        Ok(Self { dims })
+    }
+}
+
+impl Parse for DimDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name: Ident = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            DimValue::Fixed(input.parse()?)
+        } else if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            let start: LitInt = input.parse()?;
+            let inclusive = if input.peek(Token![..=]) {
+                input.parse::<Token![..=]>()?;
+                true
+            } else {
+                input.parse::<Token![..]>()?;
+                false
+            };
+            let end: LitInt = input.parse()?;
+            DimValue::Range {
+                start,
+                end,
+                inclusive,
+            }
+        } else {
+            DimValue::Free
+        };
+        let mut constraints = Vec::new();
+        if input.peek(Token![where]) {
+            input.parse::<Token![where]>()?;
+            loop {
+                let dividend = parse_dim_expr(input)?;
+                input.parse::<Token![%]>()?;
+                let divisor = parse_dim_expr(input)?;
+                input.parse::<Token![==]>()?;
+                let zero: LitInt = input.parse()?;
+                if zero.base10_parse::<u64>()? != 0 {
+                    return Err(syn::Error::new(
+                        zero.span(),
+                        "only divisibility constraints (`a % b == 0`) are supported",
+                    ));
+                }
+                constraints.push(DimConstraint { dividend, divisor });
+                if input.peek(Token![,]) {
+                    input.parse::<Token![,]>()?;
+                } else {
+                    break;
+                }
+            }
+        }
+        input.parse::<Token![;]>()?;
+        Ok(Self {
+            name,
+            value,
+            constraints,
+        })
+    }
+}
+
+impl Parse for MemorySection {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let kind = if input.peek(kw::dynamic) {
+            input.parse::<kw::dynamic>()?;
+            MemoryKindToken::Dynamic
+        } else if input.peek(kw::volatile) {
+            input.parse::<kw::volatile>()?;
+            MemoryKindToken::Volatile
+        } else if input.peek(kw::constant) {
+            input.parse::<kw::constant>()?;
+            MemoryKindToken::Constant
+        } else {
+            input.parse::<kw::persistent>()?;
+            MemoryKindToken::Persistent
+        };
+
+        let content;
+        braced!(content in input);
+        let vars = parse_repeated_items(&content)?;
+
+        Ok(Self { kind, vars })
+    }
+}
+
+impl Parse for VarDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name: Ident = input.parse()?;
+        let mut table_indices = Vec::new();
+        if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            while !content.is_empty() {
+                let ident: Ident = content.parse()?;
+                table_indices.push(ident);
+                if content.peek(Token![,]) {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+            if table_indices.is_empty() {
+                return Err(content.error("prefix table must declare at least one index"));
+            }
+        }
+        input.parse::<Token![:]>()?;
+        let dtype: Ident = input.parse()?;
+        let dims = parse_dims(input)?;
+        let attrs = attributes::parse_attrs(input)?;
+        input.parse::<Token![;]>()?;
+        Ok(Self {
+            name,
+            dtype,
+            dims,
+            init: attrs.init,
+            ref_name: attrs.ref_name,
+            pattern: attrs.pattern,
+            table_indices,
+            table: attrs.table,
+            auto_dim: attrs.auto_dim,
+            fixed: attrs.fixed,
+        })
+    }
+}
+
+impl Parse for FuncDef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<Token![fn]>()?;
+        let name: Ident = input.parse()?;
+        let content;
+        parenthesized!(content in input);
+        let params = content
+            .parse_terminated(FuncParam::parse, Token![,])?
+            .into_iter()
+            .collect();
+        input.parse::<Token![->]>()?;
+        let ret_dtype = input.parse()?;
+        let ret_dims = parse_dims(input)?;
+        let content;
+        braced!(content in input);
+        let body: proc_macro2::TokenStream = content.parse()?;
+        // Report syntax errors in the body even if the fn is never called.
+        parse_fn_body(&name).parse2(body.clone())?;
+        Ok(Self {
+            name,
+            params,
+            ret_dtype,
+            ret_dims,
+            body,
+        })
+    }
+}
+
+impl Parse for FuncParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let dtype = input.parse()?;
+        let dims = parse_dims(input)?;
+        Ok(Self { name, dtype, dims })
+    }
+}
+
+impl Parse for BlockSection {
+    fn parse(input: ParseStream) -> Result<Self> {
+        input.parse::<kw::block>()?;
+        let name: Ident = input.parse()?;
+        let content;
+        braced!(content in input);
+        let nodes = parse_repeated_items(&content)?;
+        Ok(Self { name, nodes })
+    }
+}
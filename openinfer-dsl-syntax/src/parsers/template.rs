@@ -0,0 +1,108 @@
+use proc_macro2::{Delimiter, Group, Ident, Literal, TokenStream, TokenTree};
+
+/// Substitute the compile-time value of `index` into `tokens`:
+///
+/// - a bare `i` becomes the integer literal, e.g. `x[i]` -> `x[2]`;
+/// - an identifier template `h{i}` becomes the identifier `h2`;
+/// - `{i}` inside a string literal is replaced, e.g. `"layers.{i}"`.
+pub(crate) fn substitute(tokens: TokenStream, index: &str, value: i64) -> TokenStream {
+    let mut out = Vec::new();
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Ident(ident) => {
+                if let Some(TokenTree::Group(group)) = iter.peek() {
+                    if is_placeholder(group, index) {
+                        iter.next();
+                        let name = format!("{}{}", ident, value);
+                        out.push(TokenTree::Ident(Ident::new(&name, ident.span())));
+                        continue;
+                    }
+                }
+                if ident == index {
+                    let mut lit = Literal::i64_unsuffixed(value);
+                    lit.set_span(ident.span());
+                    out.push(TokenTree::Literal(lit));
+                } else {
+                    out.push(TokenTree::Ident(ident));
+                }
+            }
+            TokenTree::Literal(lit) => {
+                let repr = lit.to_string();
+                let placeholder = format!("{{{}}}", index);
+                if repr.starts_with('"') && repr.contains(&placeholder) {
+                    let text = repr.replace(&placeholder, &value.to_string());
+                    match text.parse::<Literal>() {
+                        Ok(mut replaced) => {
+                            replaced.set_span(lit.span());
+                            out.push(TokenTree::Literal(replaced));
+                        }
+                        Err(_) => out.push(TokenTree::Literal(lit)),
+                    }
+                } else {
+                    out.push(TokenTree::Literal(lit));
+                }
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), index, value);
+                let mut replaced = Group::new(group.delimiter(), stream);
+                replaced.set_span(group.span());
+                out.push(TokenTree::Group(replaced));
+            }
+            TokenTree::Punct(punct) => out.push(TokenTree::Punct(punct)),
+        }
+    }
+    out.into_iter().collect()
+}
+
+/// Reject a nested `repeat` or loop in `tokens` that binds `index` again;
+/// substitution would turn its index into a literal. `what` names the
+/// enclosing construct, e.g. `repeat i`.
+pub(crate) fn check_shadowing(tokens: &TokenStream, index: &str, what: &str) -> syn::Result<()> {
+    let shadowed = |ident: &Ident| {
+        syn::Error::new(
+            ident.span(),
+            format!("index {} is already bound by the enclosing {}", index, what),
+        )
+    };
+    let mut prev: Option<TokenTree> = None;
+    for tt in tokens.clone() {
+        match &tt {
+            // `repeat i in ...`
+            TokenTree::Ident(ident) if ident == index => {
+                if matches!(&prev, Some(TokenTree::Ident(kw)) if kw == "repeat") {
+                    return Err(shadowed(ident));
+                }
+            }
+            TokenTree::Group(group) => {
+                // A loop header `(i in ...)`.
+                let mut inner = group.stream().into_iter();
+                if group.delimiter() == Delimiter::Parenthesis {
+                    if let (Some(TokenTree::Ident(ident)), Some(TokenTree::Ident(kw))) =
+                        (inner.next(), inner.next())
+                    {
+                        if ident == index && kw == "in" {
+                            return Err(shadowed(&ident));
+                        }
+                    }
+                }
+                check_shadowing(&group.stream(), index, what)?;
+            }
+            _ => {}
+        }
+        prev = Some(tt);
+    }
+    Ok(())
+}
+
+/// `{i}` directly after an identifier.
+fn is_placeholder(group: &Group, index: &str) -> bool {
+    if group.delimiter() != Delimiter::Brace {
+        return false;
+    }
+    let mut inner = group.stream().into_iter();
+    matches!(
+        (inner.next(), inner.next()),
+        (Some(TokenTree::Ident(ident)), None) if ident == index
+    )
+}
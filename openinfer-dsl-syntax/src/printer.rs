@@ -0,0 +1,506 @@
+//! Canonical DSL text for the AST.
+//!
+//! Printing is the inverse of parsing: `parse(print(graph))` yields the same
+//! graph, and printing that again yields the same text. Sections are
+//! separated by a blank line and nested bodies are indented by four spaces.
+//! Compile-time `repeat` and `@unroll` are printed in their expanded form.
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use syn::parse::Parser;
+
+use crate::parsers::inline::parse_fn_body;
+use crate::types::{
+    AssignNode, BlockSection, CacheAccess, CacheIndexExpr, CacheIndexValue, CallNode, Dim,
+    DimConstraint, DimDecl, DimOp, DimValue, DimsSection, FuncDef, FuncParam, GraphDsl, IndexExpr,
+    InitValue, LoopNode, MemoryKindToken, MemorySection, Node, OpAttrValue, OpNode, OpSetting,
+    RangeValue, Section, VarDecl, VarRef,
+};
+
+const INDENT: &str = "    ";
+
+impl Display for GraphDsl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", section)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Section::Dims(dims) => dims.fmt(f),
+            Section::Memory(mem) => mem.fmt(f),
+            Section::Block(block) => block.fmt(f),
+            Section::Func(func) => func.fmt(f),
+        }
+    }
+}
+
+impl Display for DimsSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.dims.iter().map(ToString::to_string).collect();
+        write_braced(f, "dims", &lines)
+    }
+}
+
+impl Display for DimDecl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        match &self.value {
+            DimValue::Free => {}
+            DimValue::Fixed(lit) => write!(f, " = {}", lit)?,
+            DimValue::Range {
+                start,
+                end,
+                inclusive,
+            } => write!(f, ": {}{}{}", start, range_dots(*inclusive), end)?,
+        }
+        if !self.constraints.is_empty() {
+            let constraints: Vec<String> =
+                self.constraints.iter().map(ToString::to_string).collect();
+            write!(f, " where {}", constraints.join(", "))?;
+        }
+        write!(f, ";")
+    }
+}
+
+impl Display for DimConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} % {} == 0", self.dividend, self.divisor)
+    }
+}
+
+impl Display for MemoryKindToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MemoryKindToken::Dynamic => "dynamic",
+            MemoryKindToken::Volatile => "volatile",
+            MemoryKindToken::Constant => "constant",
+            MemoryKindToken::Persistent => "persistent",
+        })
+    }
+}
+
+impl Display for MemorySection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.vars.iter().map(ToString::to_string).collect();
+        write_braced(f, &self.kind.to_string(), &lines)
+    }
+}
+
+impl Display for VarDecl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.table_indices.is_empty() {
+            write!(f, "({})", join(&self.table_indices))?;
+        }
+        write!(f, ": {}{}", self.dtype, Dims(&self.dims))?;
+        if let Some(init) = &self.init {
+            write!(f, " @init({})", init)?;
+        }
+        if let Some(ref_name) = &self.ref_name {
+            write!(f, " @ref({:?})", ref_name.value())?;
+        }
+        if let Some(pattern) = &self.pattern {
+            write!(f, " @pattern({:?})", pattern.value())?;
+        }
+        if self.table {
+            write!(f, " @table")?;
+        }
+        if !self.auto_dim.is_empty() {
+            write!(f, " @auto_dim({})", join(&self.auto_dim))?;
+        }
+        if !self.fixed.is_empty() {
+            let fixed: Vec<String> = self
+                .fixed
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            write!(f, " @fixed({})", fixed.join(", "))?;
+        }
+        write!(f, ";")
+    }
+}
+
+impl Display for InitValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InitValue::Float { lit, negative } => write!(f, "{}{}", sign(*negative), lit),
+            InitValue::Int { lit, negative } => write!(f, "{}{}", sign(*negative), lit),
+            InitValue::Bool { lit } => write!(f, "{}", lit.value),
+        }
+    }
+}
+
+/// `[B, D]`, or nothing for a scalar.
+pub(crate) struct Dims<'a>(pub(crate) &'a [Dim]);
+
+impl Display for Dims<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        write!(f, "[{}]", join(self.0))
+    }
+}
+
+impl Display for Dim {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Dim::Ident(ident) => write!(f, "{}", ident),
+            Dim::Lit(lit) => write!(f, "{}", lit),
+            Dim::Binary { op, left, right } => {
+                write_operand(f, left, op.precedence(), false)?;
+                write!(f, "{}", op)?;
+                write_operand(f, right, op.precedence(), true)
+            }
+            Dim::CeilDiv { num, den } => {
+                write!(f, "ceil(")?;
+                write_operand(f, num, DimOp::Div.precedence(), false)?;
+                write!(f, "/")?;
+                write_operand(f, den, DimOp::Div.precedence(), true)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Parenthesise `dim` when it binds looser than its parent operator, or as
+/// tightly on the right of a non-associative one (`a-(b-c)`, `a/(b*c)`).
+fn write_operand(f: &mut Formatter<'_>, dim: &Dim, parent: u8, right: bool) -> fmt::Result {
+    let needs_parens = match dim {
+        Dim::Binary { op, .. } => op.precedence() < parent || (right && op.precedence() == parent),
+        _ => false,
+    };
+    if needs_parens {
+        write!(f, "({})", dim)
+    } else {
+        write!(f, "{}", dim)
+    }
+}
+
+impl DimOp {
+    fn precedence(self) -> u8 {
+        match self {
+            DimOp::Add | DimOp::Sub => 1,
+            DimOp::Mul | DimOp::Div => 2,
+        }
+    }
+}
+
+impl Display for DimOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DimOp::Add => "+",
+            DimOp::Sub => "-",
+            DimOp::Mul => "*",
+            DimOp::Div => "/",
+        })
+    }
+}
+
+impl Display for FuncDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "fn {}({}) -> {}{} {{",
+            self.name,
+            join(&self.params),
+            self.ret_dtype,
+            Dims(&self.ret_dims)
+        )?;
+        match parse_fn_body(&self.name).parse2(self.body.clone()) {
+            Ok((nodes, result)) => {
+                write_nodes(f, &nodes, 1)?;
+                writeln!(f, "{}return {};", INDENT, result)?;
+            }
+            // Bodies are checked when the fn is parsed; keep the tokens as a
+            // fallback rather than failing to print.
+            Err(_) => writeln!(f, "{}{}", INDENT, self.body)?,
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Display for FuncParam {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}{}", self.name, self.dtype, Dims(&self.dims))
+    }
+}
+
+impl Display for BlockSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "block {} {{", self.name)?;
+        write_nodes(f, &self.nodes, 1)?;
+        write!(f, "}}")
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        write_node(&mut out, self, 0)?;
+        f.write_str(out.trim_end_matches('\n'))
+    }
+}
+
+fn write_nodes(out: &mut impl Write, nodes: &[Node], depth: usize) -> fmt::Result {
+    for node in nodes {
+        write_node(out, node, depth)?;
+    }
+    Ok(())
+}
+
+/// Write `node` as one or more lines at `depth`.
+fn write_node(out: &mut impl Write, node: &Node, depth: usize) -> fmt::Result {
+    let indent = INDENT.repeat(depth);
+    match node {
+        Node::Loop(loop_node) if loop_node.unroll.is_some() => {
+            write_nodes(out, &loop_node.body, depth)
+        }
+        Node::Loop(loop_node) => {
+            writeln!(out, "{}{} {{", indent, LoopHeader(loop_node))?;
+            write_nodes(out, &loop_node.body, depth + 1)?;
+            writeln!(out, "{}}}", indent)
+        }
+        _ => writeln!(out, "{}{}", indent, Statement(node)),
+    }
+}
+
+/// A single-line node.
+struct Statement<'a>(&'a Node);
+
+impl Display for Statement<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Node::Assign(assign) => write!(f, "{}", assign),
+            Node::Op(op) => write!(f, "{}", op),
+            Node::Branch(branch) => match (&branch.cond, &branch.else_block) {
+                (Some(cond), Some(else_block)) => {
+                    write!(f, "branch {} {} {};", cond, branch.then_block, else_block)
+                }
+                _ => write!(f, "branch {};", branch.then_block),
+            },
+            Node::Barrier => write!(f, "barrier;"),
+            Node::Dep(dep) => write!(f, "dep after({}) before({});", dep.after, dep.before),
+            Node::CacheRead(node) => write!(f, "cache.read {} >> {};", node.src, node.dst),
+            Node::CacheWrite(node) => write!(f, "cache.write {} >> {};", node.src, node.dst),
+            Node::CacheInc(node) => {
+                write!(f, "cache.increment {}{};", Amount(node.amount), node.target)
+            }
+            Node::CacheDec(node) => {
+                write!(f, "cache.decrement {}{};", Amount(node.amount), node.target)
+            }
+            Node::CacheReset(node) => write!(f, "cache.reset {};", node.target),
+            Node::Transfer(node) => write!(f, "transfer {} >> {};", node.src, node.dst),
+            Node::Loop(loop_node) => write!(f, "{} {{ .. }}", LoopHeader(loop_node)),
+            Node::Call(call) => write!(f, "{}", call),
+            Node::Yield(node) => write!(f, "yield {};", join(&node.vars)),
+            Node::Await(node) => write!(f, "await {};", join(&node.vars)),
+            Node::Return => write!(f, "return;"),
+        }
+    }
+}
+
+impl Display for AssignNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "assign {}: {}{};",
+            self.name,
+            self.dtype,
+            Dims(&self.dims)
+        )
+    }
+}
+
+impl Display for OpNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut args: Vec<String> = self.inputs.iter().map(ToString::to_string).collect();
+        args.extend(self.settings.iter().map(ToString::to_string));
+        write!(
+            f,
+            "op {}({}) >> {};",
+            self.name,
+            args.join(", "),
+            self.output
+        )
+    }
+}
+
+impl Display for OpSetting {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+impl Display for OpAttrValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            // `{:?}` keeps the decimal point, so the value re-parses as a float.
+            OpAttrValue::Float(value) => write!(f, "{:?}", value),
+            OpAttrValue::Double(value) => write!(f, "{:?}", value),
+            OpAttrValue::Int(value) => write!(f, "{}", value),
+            OpAttrValue::Bool(value) => write!(f, "{}", value),
+            OpAttrValue::String(value) => write!(f, "{:?}", value),
+            OpAttrValue::IntList(values) => write!(f, "[{}]", join(values)),
+            OpAttrValue::DTypeList(values) => write!(f, "[{}]", join(values)),
+            OpAttrValue::Var(ident) => write!(f, "{}", ident),
+            OpAttrValue::VarList(values) => write!(f, "[{}]", join(values)),
+        }
+    }
+}
+
+impl Display for CallNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "call {}({}) >> {};",
+            self.name,
+            join(&self.args),
+            self.output
+        )
+    }
+}
+
+/// `loop name (i in start..end step k)`
+pub(crate) struct LoopHeader<'a>(pub(crate) &'a LoopNode);
+
+impl Display for LoopHeader<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let node = self.0;
+        write!(
+            f,
+            "loop {} ({} in {}{}{}",
+            node.name,
+            node.index,
+            node.start,
+            range_dots(node.inclusive),
+            node.end
+        )?;
+        if let Some(step) = &node.step {
+            write!(f, " step {}", step)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for RangeValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeValue::Ident(ident) => write!(f, "{}", ident),
+            RangeValue::Lit(lit) => write!(f, "{}", lit),
+            RangeValue::Expr(dim) => write!(f, "{}", dim),
+        }
+    }
+}
+
+impl Display for VarRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.indices.is_empty() {
+            write!(f, "[{}]", join(&self.indices))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for IndexExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexExpr::Ident(ident) => write!(f, "{}", ident),
+            IndexExpr::Lit(lit) => write!(f, "{}", lit),
+        }
+    }
+}
+
+impl Display for CacheAccess {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.bracketed {
+            write!(f, "[{}]", join(&self.indices))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CacheIndexExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheIndexExpr::Single(value) => write!(f, "{}", value),
+            CacheIndexExpr::Slice { start, end } => {
+                if let Some(start) = start {
+                    write!(f, "{}", start)?;
+                }
+                write!(f, "..")?;
+                if let Some(end) = end {
+                    write!(f, "{}", end)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for CacheIndexValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheIndexValue::Ident(ident) => write!(f, "{}", ident),
+            CacheIndexValue::Lit(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// `N ` before a cache counter target, omitted for the default of 1.
+struct Amount(i64);
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0 != 1 {
+            write!(f, "{} ", self.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// `keyword {` + one indented line per item + `}`; `keyword {}` when empty.
+fn write_braced(f: &mut Formatter<'_>, keyword: &str, lines: &[String]) -> fmt::Result {
+    if lines.is_empty() {
+        return write!(f, "{} {{}}", keyword);
+    }
+    writeln!(f, "{} {{", keyword)?;
+    for line in lines {
+        writeln!(f, "{}{}", INDENT, line)?;
+    }
+    write!(f, "}}")
+}
+
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn range_dots(inclusive: bool) -> &'static str {
+    if inclusive {
+        "..="
+    } else {
+        ".."
+    }
+}
+
+fn sign(negative: bool) -> &'static str {
+    if negative {
+        "-"
+    } else {
+        ""
+    }
+}
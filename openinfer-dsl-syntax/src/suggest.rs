@@ -0,0 +1,66 @@
+//! Edit-distance helpers for "did you mean" hints in diagnostics.
+
+/// Edit distance counting insertions, deletions, substitutions and adjacent
+/// transpositions (`tabel` -> `table`) as one edit each.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Closest candidate to `name`, if any is near enough to be a plausible typo.
+pub(crate) fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let limit = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Suffix for an error message, e.g. "; did you mean `add`?".
+pub(crate) fn did_you_mean<'a, I>(name: &str, candidates: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    match closest(name, candidates) {
+        Some(candidate) => format!("; did you mean `{}`?", candidate),
+        None => String::new(),
+    }
+}
+
+/// Message for a token outside a closed set, e.g.
+/// "unsupported attribute `@tabel`; did you mean `table`? (expected one of: ...)".
+pub(crate) fn unsupported(what: &str, found: Option<&str>, candidates: &[&str]) -> String {
+    let mut message = format!("unsupported {}", what);
+    if let Some(found) = found {
+        message.push_str(&format!(" `{}`", found));
+        message.push_str(&did_you_mean(found, candidates.iter().copied()));
+    }
+    message.push_str(&format!(" (expected one of: {})", candidates.join(", ")));
+    message
+}
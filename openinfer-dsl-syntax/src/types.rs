@@ -0,0 +1,494 @@
+//! The DSL syntax tree.
+//!
+//! [`GraphDsl`] is produced by parsing (`syn::parse2`, `syn::parse_str`) and
+//! printed back to canonical DSL text by its `Display` impl. Compile-time
+//! `repeat` blocks, `@unroll` loops and `call`s are already expanded when
+//! parsing returns; identifiers and literals keep their source spans.
+
+use syn::{Ident, LitBool, LitFloat, LitInt, LitStr};
+
+/// A whole graph: the sections in source order.
+pub struct GraphDsl {
+    /// Sections in source order.
+    pub sections: Vec<Section>,
+}
+
+/// A top-level section.
+pub enum Section {
+    /// `dims { ... }`
+    Dims(DimsSection),
+    /// `dynamic`, `volatile`, `constant` or `persistent { ... }`
+    Memory(MemorySection),
+    /// `block name { ... }`
+    Block(BlockSection),
+    /// `fn name(...) -> dtype[dims] { ... }`
+    Func(FuncDef),
+}
+
+/// `fn mlp(x: f32[B, D]) -> f32[B, D] { ...; return y; }`
+#[derive(Clone)]
+pub struct FuncDef {
+    /// Name used by `call`.
+    pub name: Ident,
+    /// Parameters in order.
+    pub params: Vec<FuncParam>,
+    /// Dtype of the returned value.
+    pub ret_dtype: Ident,
+    /// Shape of the returned value.
+    pub ret_dims: Vec<Dim>,
+    /// Source tokens of the body, re-parsed for every call site.
+    pub body: proc_macro2::TokenStream,
+}
+
+/// `x: f32[B, D]` in a fn signature.
+#[derive(Clone)]
+pub struct FuncParam {
+    /// Parameter name.
+    pub name: Ident,
+    /// Expected dtype of the argument.
+    pub dtype: Ident,
+    /// Expected shape of the argument; bare symbols bind to the caller's dims.
+    pub dims: Vec<Dim>,
+}
+
+/// `dims { B: 1..=64; D = 768; H where D % H == 0; }`
+pub struct DimsSection {
+    /// Declarations in source order.
+    pub dims: Vec<DimDecl>,
+}
+
+/// One declaration of a `dims` section.
+pub struct DimDecl {
+    /// Dimension name.
+    pub name: Ident,
+    /// Allowed values.
+    pub value: DimValue,
+    /// `where` constraints.
+    pub constraints: Vec<DimConstraint>,
+}
+
+/// Allowed values of a symbolic dimension.
+pub enum DimValue {
+    /// Any positive value bound at runtime.
+    Free,
+    /// `D = 768`
+    Fixed(LitInt),
+    /// `B: 1..=64` or `S: 1..4096`
+    Range {
+        /// Lower bound.
+        start: LitInt,
+        /// Upper bound.
+        end: LitInt,
+        /// `..=` instead of `..`.
+        inclusive: bool,
+    },
+}
+
+/// `dividend % divisor == 0`
+pub struct DimConstraint {
+    /// Left-hand side of `%`.
+    pub dividend: Dim,
+    /// Right-hand side of `%`.
+    pub divisor: Dim,
+}
+
+/// `dynamic { ... }` and the other memory sections.
+pub struct MemorySection {
+    /// Which memory the variables live in.
+    pub kind: MemoryKindToken,
+    /// Declarations in source order.
+    pub vars: Vec<VarDecl>,
+}
+
+/// Keyword of a memory section.
+pub enum MemoryKindToken {
+    /// `dynamic`
+    Dynamic,
+    /// `volatile`
+    Volatile,
+    /// `constant`
+    Constant,
+    /// `persistent`
+    Persistent,
+}
+
+/// `name(i, j): dtype[dims] @attrs...;`
+pub struct VarDecl {
+    /// Variable name.
+    pub name: Ident,
+    /// Element dtype.
+    pub dtype: Ident,
+    /// Shape; empty for scalars.
+    pub dims: Vec<Dim>,
+    /// `@init(value)`
+    pub init: Option<InitValue>,
+    /// `@ref("name")`
+    pub ref_name: Option<LitStr>,
+    /// `@pattern("glob")`
+    pub pattern: Option<LitStr>,
+    /// Table indices `(i, j)` after the name.
+    pub table_indices: Vec<Ident>,
+    /// `@table`
+    pub table: bool,
+    /// `@auto_dim(i, ...)`
+    pub auto_dim: Vec<Ident>,
+    /// `@fixed(i=1, ...)`
+    pub fixed: Vec<(Ident, LitInt)>,
+}
+
+/// A dimension expression in a shape.
+#[derive(Clone)]
+pub enum Dim {
+    /// Symbolic dim such as `B`.
+    Ident(Ident),
+    /// Constant dim.
+    Lit(LitInt),
+    /// `left op right`
+    Binary {
+        /// Operator.
+        op: DimOp,
+        /// Left operand.
+        left: Box<Dim>,
+        /// Right operand.
+        right: Box<Dim>,
+    },
+    /// `ceil(num / den)`.
+    CeilDiv {
+        /// Numerator.
+        num: Box<Dim>,
+        /// Denominator.
+        den: Box<Dim>,
+    },
+}
+
+impl Dim {
+    /// Span of the leftmost token, for diagnostics.
+    pub fn span(&self) -> proc_macro2::Span {
+        match self {
+            Dim::Ident(ident) => ident.span(),
+            Dim::Lit(lit) => lit.span(),
+            Dim::Binary { left, .. } => left.span(),
+            Dim::CeilDiv { num, .. } => num.span(),
+        }
+    }
+}
+
+/// Operator of [`Dim::Binary`].
+#[derive(Clone, Copy, PartialEq)]
+pub enum DimOp {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// Floor division.
+    Div,
+}
+
+/// Value of `@init(...)`.
+pub enum InitValue {
+    /// Float literal, with its sign.
+    Float {
+        /// Literal without the sign.
+        lit: LitFloat,
+        /// Preceded by `-`.
+        negative: bool,
+    },
+    /// Integer literal, with its sign.
+    Int {
+        /// Literal without the sign.
+        lit: LitInt,
+        /// Preceded by `-`.
+        negative: bool,
+    },
+    /// `true` or `false`.
+    Bool {
+        /// The literal.
+        lit: LitBool,
+    },
+}
+
+/// `block name { ... }`
+pub struct BlockSection {
+    /// Block name, the target of `branch`.
+    pub name: Ident,
+    /// Nodes in source order.
+    pub nodes: Vec<Node>,
+}
+
+/// A statement of a block, loop or fn body.
+pub enum Node {
+    /// `assign x: f32[B];`
+    Assign(AssignNode),
+    /// `op add(a, b) >> c;`
+    Op(OpNode),
+    /// `branch cond then else;` or `branch target;`
+    Branch(BranchNode),
+    /// `barrier;`
+    Barrier,
+    /// `dep after(a) before(b);`
+    Dep(DepNode),
+    /// `cache.read src[...] >> dst;`
+    CacheRead(CacheReadNode),
+    /// `cache.write src >> dst[...];`
+    CacheWrite(CacheWriteNode),
+    /// `cache.increment [n] target;`
+    CacheInc(CacheIncNode),
+    /// `cache.decrement [n] target;`
+    CacheDec(CacheDecNode),
+    /// `cache.reset target[...];`
+    CacheReset(CacheResetNode),
+    /// `transfer src >> dst;`
+    Transfer(TransferNode),
+    /// `loop name (i in a..b) { ... }`
+    Loop(LoopNode),
+    /// `call f(a, b) >> y;`
+    Call(CallNode),
+    /// `yield a, b;`
+    Yield(YieldNode),
+    /// `await a, b;`
+    Await(AwaitNode),
+    /// `return;`
+    Return,
+}
+
+/// `assign name: dtype[dims];`
+pub struct AssignNode {
+    /// Temporary name.
+    pub name: Ident,
+    /// Element dtype.
+    pub dtype: Ident,
+    /// Shape.
+    pub dims: Vec<Dim>,
+}
+
+/// `op name(inputs, settings) >> output;`
+pub struct OpNode {
+    /// Op name, looked up in the registry.
+    pub name: Ident,
+    /// Positional inputs.
+    pub inputs: Vec<VarRef>,
+    /// `name=value` settings in source order.
+    pub settings: Vec<OpSetting>,
+    /// Output variable.
+    pub output: Ident,
+}
+
+/// `branch cond then else;` or the unconditional `branch then;`
+pub struct BranchNode {
+    /// Condition variable, if conditional.
+    pub cond: Option<Ident>,
+    /// Block taken when the condition holds, or always.
+    pub then_block: Ident,
+    /// Block taken otherwise.
+    pub else_block: Option<Ident>,
+}
+
+/// `dep after(a) before(b);`
+pub struct DepNode {
+    /// Variable that must be ready first.
+    pub after: Ident,
+    /// Variable that waits on `after`.
+    pub before: Ident,
+}
+
+/// `loop name (index in start..end step k) { body }`
+pub struct LoopNode {
+    /// Loop name.
+    pub name: Ident,
+    /// Index variable.
+    pub index: Ident,
+    /// First index value.
+    pub start: RangeValue,
+    /// End of the range.
+    pub end: RangeValue,
+    /// `..=` instead of `..`.
+    pub inclusive: bool,
+    /// `step k`; defaults to 1.
+    pub step: Option<LitInt>,
+    /// Source body of an `@unroll` loop. `body` then holds every iteration,
+    /// already expanded with the index substituted.
+    pub unroll: Option<proc_macro2::TokenStream>,
+    /// Loop body.
+    pub body: Vec<Node>,
+}
+
+/// `cache.read src >> dst;`
+pub struct CacheReadNode {
+    /// Cached variable and slice.
+    pub src: CacheAccess,
+    /// Destination variable.
+    pub dst: VarRef,
+}
+
+/// `cache.write src >> dst;`
+pub struct CacheWriteNode {
+    /// Source variable.
+    pub src: VarRef,
+    /// Cached variable and slice.
+    pub dst: CacheAccess,
+}
+
+/// `cache.increment amount target;`
+pub struct CacheIncNode {
+    /// Counter variable.
+    pub target: Ident,
+    /// Amount; 1 when omitted.
+    pub amount: i64,
+}
+
+/// `cache.decrement amount target;`
+pub struct CacheDecNode {
+    /// Counter variable.
+    pub target: Ident,
+    /// Amount; 1 when omitted.
+    pub amount: i64,
+}
+
+/// `cache.reset target;`
+pub struct CacheResetNode {
+    /// Cached variable and slice.
+    pub target: CacheAccess,
+}
+
+/// `call mlp(x) >> y;`
+pub struct CallNode {
+    /// Called fn.
+    pub name: Ident,
+    /// Arguments, one per parameter.
+    pub args: Vec<Ident>,
+    /// Variable receiving the result.
+    pub output: Ident,
+    /// The function body with temporaries renamed and parameters, dims and
+    /// the result bound to this call site; filled in after parsing.
+    pub inlined: Vec<Node>,
+}
+
+/// `transfer src >> dst;`
+pub struct TransferNode {
+    /// Source variable.
+    pub src: VarRef,
+    /// Destination variable.
+    pub dst: VarRef,
+}
+
+/// `yield a, b;`
+pub struct YieldNode {
+    /// Yielded variables.
+    pub vars: Vec<Ident>,
+}
+
+/// `await a, b;`
+pub struct AwaitNode {
+    /// Awaited variables.
+    pub vars: Vec<Ident>,
+}
+
+/// `name=value` in an op call.
+#[derive(Clone)]
+pub struct OpSetting {
+    /// Setting name.
+    pub name: Ident,
+    /// Setting value.
+    pub value: OpAttrValue,
+}
+
+/// Value of an op setting.
+#[derive(Clone)]
+pub enum OpAttrValue {
+    /// Single-precision float; the parser produces [`OpAttrValue::Double`].
+    Float(f32),
+    /// Float literal, including `inf` and `-inf`.
+    Double(f64),
+    /// Integer literal.
+    Int(i64),
+    /// `true` or `false`.
+    Bool(bool),
+    /// String literal.
+    String(String),
+    /// `[0, -1]`
+    IntList(Vec<i64>),
+    /// `[i32, i64]`
+    DTypeList(Vec<Ident>),
+    /// Identifier such as a dtype or variable name.
+    Var(Ident),
+    /// `[a, b]`
+    VarList(Vec<Ident>),
+}
+
+/// Argument of an op call before inputs and settings are separated.
+pub enum OpArg {
+    /// Positional input.
+    Input(VarRef),
+    /// `name=value`
+    Setting(OpSetting),
+}
+
+/// A variable, optionally indexed as `x[i, 0]`.
+pub struct VarRef {
+    /// Variable name.
+    pub name: Ident,
+    /// Indices; empty when not indexed.
+    pub indices: Vec<IndexExpr>,
+}
+
+/// A cached variable with an optional slice, `kv[i, 0..j]`.
+pub struct CacheAccess {
+    /// Variable name.
+    pub name: Ident,
+    /// Indices and slices.
+    pub indices: Vec<CacheIndexExpr>,
+    /// Written with brackets, even if empty.
+    pub bracketed: bool,
+}
+
+/// One position of a [`CacheAccess`].
+pub enum CacheIndexExpr {
+    /// `i` or `0`
+    Single(CacheIndexValue),
+    /// `a..b`, `..b`, `a..` or `..`
+    Slice {
+        /// Start of the slice.
+        start: Option<CacheIndexValue>,
+        /// End of the slice.
+        end: Option<CacheIndexValue>,
+    },
+}
+
+/// Index or slice bound of a [`CacheAccess`].
+pub enum CacheIndexValue {
+    /// Table index or loop variable.
+    Ident(Ident),
+    /// Constant, possibly negative.
+    Lit(i64),
+}
+
+/// Index of a [`VarRef`].
+pub enum IndexExpr {
+    /// Loop or table index.
+    Ident(Ident),
+    /// Constant index.
+    Lit(LitInt),
+}
+
+/// Bound of a loop range.
+pub enum RangeValue {
+    /// Symbolic bound such as `N`.
+    Ident(Ident),
+    /// Constant bound.
+    Lit(LitInt),
+    /// Arithmetic bound such as `S-1` or `L*2`.
+    Expr(Dim),
+}
+
+impl RangeValue {
+    /// Span of the bound, for diagnostics.
+    pub fn span(&self) -> proc_macro2::Span {
+        match self {
+            RangeValue::Ident(ident) => ident.span(),
+            RangeValue::Lit(lit) => lit.span(),
+            RangeValue::Expr(dim) => dim.span(),
+        }
+    }
+}
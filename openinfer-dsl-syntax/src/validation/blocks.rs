@@ -0,0 +1,77 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use proc_macro2::Span;
+use syn::Ident;
+
+use crate::diagnostics::Diagnostics;
+use crate::suggest;
+use crate::types::{GraphDsl, Node, Section};
+
+/// Check that block names are unique, an `entry` block exists and every
+/// branch/dep target names a declared block.
+pub(crate) fn check_blocks(graph: &GraphDsl, diagnostics: &mut Diagnostics) {
+    let mut blocks: HashMap<String, &Ident> = HashMap::new();
+    for section in &graph.sections {
+        if let Section::Block(block) = section {
+            match blocks.entry(block.name.to_string()) {
+                Entry::Occupied(entry) => diagnostics.push(syn::Error::new(
+                    block.name.span(),
+                    format!("duplicate block: {}", entry.key()),
+                )),
+                Entry::Vacant(entry) => {
+                    entry.insert(&block.name);
+                }
+            }
+        }
+    }
+    if !blocks.contains_key("entry") {
+        diagnostics.push(syn::Error::new(
+            Span::call_site(),
+            "graph must declare an `entry` block",
+        ));
+    }
+    for section in &graph.sections {
+        if let Section::Block(block) = section {
+            check_targets(&block.nodes, &blocks, diagnostics);
+        }
+    }
+}
+
+fn check_targets(nodes: &[Node], blocks: &HashMap<String, &Ident>, diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Branch(branch) => {
+                diagnostics.record(check_target(&branch.then_block, blocks));
+                if let Some(else_block) = &branch.else_block {
+                    diagnostics.record(check_target(else_block, blocks));
+                }
+            }
+            Node::Dep(dep) => {
+                diagnostics.record(check_target(&dep.after, blocks));
+                diagnostics.record(check_target(&dep.before, blocks));
+            }
+            Node::Loop(loop_node) => check_targets(&loop_node.body, blocks, diagnostics),
+            Node::Call(call) => check_targets(&call.inlined, blocks, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn check_target(target: &Ident, blocks: &HashMap<String, &Ident>) -> syn::Result<()> {
+    let key = target.to_string();
+    if blocks.contains_key(&key) {
+        return Ok(());
+    }
+    let mut names: Vec<&str> = blocks.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    Err(syn::Error::new(
+        target.span(),
+        format!(
+            "unknown block: {}{} (declared blocks: {})",
+            key,
+            suggest::did_you_mean(&key, names.iter().copied()),
+            names.join(", ")
+        ),
+    ))
+}
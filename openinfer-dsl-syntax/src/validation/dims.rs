@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use syn::Ident;
+
+use crate::diagnostics::Diagnostics;
+use crate::suggest;
+use crate::types::{Dim, DimDecl, DimOp, DimValue, GraphDsl, Node, Section};
+
+/// Check the `dims` section: every symbol is declared once, ranges are
+/// non-empty, constraints between fixed dims hold and every dimension used
+/// by a variable is declared.
+///
+/// Graphs without a `dims` section keep using implicit symbols.
+pub(crate) fn check_dims(graph: &GraphDsl, diagnostics: &mut Diagnostics) {
+    let has_section = graph
+        .sections
+        .iter()
+        .any(|section| matches!(section, Section::Dims(_)));
+    if !has_section {
+        return;
+    }
+    let decls: Vec<&DimDecl> = graph
+        .sections
+        .iter()
+        .filter_map(|section| match section {
+            Section::Dims(dims) => Some(&dims.dims),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let mut declared: HashMap<String, &DimDecl> = HashMap::new();
+    for decl in &decls {
+        let key = decl.name.to_string();
+        if declared.contains_key(&key) {
+            diagnostics.push(syn::Error::new(
+                decl.name.span(),
+                format!("duplicate dimension: {}", key),
+            ));
+            continue;
+        }
+        diagnostics.record(check_value(decl));
+        declared.insert(key, decl);
+    }
+
+    let fixed: HashMap<String, i64> = declared
+        .iter()
+        .filter_map(|(name, decl)| match &decl.value {
+            DimValue::Fixed(lit) => lit.base10_parse().ok().map(|value| (name.clone(), value)),
+            _ => None,
+        })
+        .collect();
+
+    let mut used = Vec::new();
+    for decl in &decls {
+        for constraint in &decl.constraints {
+            collect_idents(&constraint.dividend, &mut used);
+            collect_idents(&constraint.divisor, &mut used);
+            let dividend = diagnostics.record(eval(&constraint.dividend, &fixed));
+            let divisor = diagnostics.record(eval(&constraint.divisor, &fixed));
+            if let (Some(Some(dividend)), Some(Some(divisor))) = (dividend, divisor) {
+                if divisor == 0 || dividend.checked_rem(divisor).is_some_and(|rem| rem != 0) {
+                    diagnostics.push(syn::Error::new(
+                        constraint.dividend.span(),
+                        format!(
+                            "dimension constraint does not hold: {} is not divisible by {}",
+                            dividend, divisor
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    for section in &graph.sections {
+        match section {
+            Section::Memory(mem) => {
+                for var in &mem.vars {
+                    for dim in &var.dims {
+                        collect_idents(dim, &mut used);
+                    }
+                }
+            }
+            Section::Block(block) => collect_assign_dims(&block.nodes, &mut used),
+            Section::Dims(_) | Section::Func(_) => {}
+        }
+    }
+
+    for ident in used {
+        let key = ident.to_string();
+        if !declared.contains_key(&key) {
+            diagnostics.push(syn::Error::new(
+                ident.span(),
+                format!(
+                    "undeclared dimension: {}{}",
+                    key,
+                    suggest::did_you_mean(&key, declared.keys().map(String::as_str))
+                ),
+            ));
+        }
+    }
+}
+
+fn check_value(decl: &DimDecl) -> syn::Result<()> {
+    match &decl.value {
+        DimValue::Free => Ok(()),
+        DimValue::Fixed(lit) => {
+            if lit.base10_parse::<u64>()? == 0 {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("dimension {} must be positive", decl.name),
+                ));
+            }
+            Ok(())
+        }
+        DimValue::Range {
+            start,
+            end,
+            inclusive,
+        } => {
+            let min = start.base10_parse::<u64>()?;
+            let max = end.base10_parse::<u64>()?;
+            if min == 0 {
+                return Err(syn::Error::new(
+                    start.span(),
+                    format!("dimension {} must be positive", decl.name),
+                ));
+            }
+            if max < min || (max == min && !inclusive) {
+                return Err(syn::Error::new(
+                    end.span(),
+                    format!("empty range for dimension {}", decl.name),
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn collect_assign_dims<'a>(nodes: &'a [Node], used: &mut Vec<&'a Ident>) {
+    for node in nodes {
+        match node {
+            Node::Assign(assign) => {
+                for dim in &assign.dims {
+                    collect_idents(dim, used);
+                }
+            }
+            Node::Loop(loop_node) => collect_assign_dims(&loop_node.body, used),
+            Node::Call(call) => collect_assign_dims(&call.inlined, used),
+            _ => {}
+        }
+    }
+}
+
+fn collect_idents<'a>(dim: &'a Dim, used: &mut Vec<&'a Ident>) {
+    match dim {
+        Dim::Ident(ident) => used.push(ident),
+        Dim::Lit(_) => {}
+        Dim::Binary { left, right, .. } => {
+            collect_idents(left, used);
+            collect_idents(right, used);
+        }
+        Dim::CeilDiv { num, den } => {
+            collect_idents(num, used);
+            collect_idents(den, used);
+        }
+    }
+}
+
+/// Evaluate `dim` when every symbol in it has a fixed value.
+fn eval(dim: &Dim, fixed: &HashMap<String, i64>) -> syn::Result<Option<i64>> {
+    let overflow = || syn::Error::new(dim.span(), "dimension expression overflows i64");
+    Ok(match dim {
+        Dim::Ident(ident) => fixed.get(&ident.to_string()).copied(),
+        Dim::Lit(lit) => lit.base10_parse().ok(),
+        Dim::Binary { op, left, right } => {
+            let (Some(left), Some(right)) = (eval(left, fixed)?, eval(right, fixed)?) else {
+                return Ok(None);
+            };
+            let value = match op {
+                DimOp::Add => left.checked_add(right),
+                DimOp::Sub => left.checked_sub(right),
+                DimOp::Mul => left.checked_mul(right),
+                DimOp::Div if right == 0 => return Ok(None),
+                DimOp::Div => left.checked_div_euclid(right),
+            };
+            Some(value.ok_or_else(overflow)?)
+        }
+        Dim::CeilDiv { num, den } => {
+            let (Some(num), Some(den)) = (eval(num, fixed)?, eval(den, fixed)?) else {
+                return Ok(None);
+            };
+            if den == 0 {
+                return Ok(None);
+            }
+            let value = num
+                .checked_neg()
+                .and_then(|num| num.checked_div_euclid(den))
+                .and_then(i64::checked_neg);
+            Some(value.ok_or_else(overflow)?)
+        }
+    })
+}
@@ -0,0 +1,139 @@
+use syn::Ident;
+
+use crate::types::{GraphDsl, Node, OpAttrValue, OpNode, Section, TransferNode};
+
+use super::ops::registry::{self, DTypeRule};
+use super::ops::schema;
+use super::symbols::SymbolTable;
+use crate::diagnostics::Diagnostics;
+
+/// Propagate dtypes through ops and transfers and compare them with the
+/// declared dtypes of the variables they write.
+pub(crate) fn check_dtypes(graph: &GraphDsl, symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        if let Section::Block(block) = section {
+            check_nodes(&block.nodes, symbols, diagnostics);
+        }
+    }
+}
+
+fn check_nodes(nodes: &[Node], symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Op(op) => {
+                diagnostics.record(check_op(op, symbols));
+            }
+            Node::Transfer(node) => {
+                diagnostics.record(check_transfer(node, symbols));
+            }
+            Node::Loop(loop_node) => check_nodes(&loop_node.body, symbols, diagnostics),
+            Node::Call(call) => check_nodes(&call.inlined, symbols, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn dtype_of<'a>(symbols: &'a SymbolTable, name: &Ident) -> Option<&'a Ident> {
+    symbols.get(name).map(|symbol| symbol.dtype)
+}
+
+fn check_op(op: &OpNode, symbols: &SymbolTable) -> syn::Result<()> {
+    // Unknown ops and malformed settings are reported by the ops pass.
+    let Some(spec) = registry::find(&op.name) else {
+        return Ok(());
+    };
+    let Ok(settings) = schema::resolve(spec, &op.name, &op.settings) else {
+        return Ok(());
+    };
+
+    let mut input_dtype: Option<&Ident> = None;
+    for input in &op.inputs {
+        let Some(dtype) = dtype_of(symbols, &input.name) else {
+            return Ok(());
+        };
+        match input_dtype {
+            None => input_dtype = Some(dtype),
+            Some(first) if first == dtype => {}
+            Some(first) => {
+                return Err(syn::Error::new(
+                    input.name.span(),
+                    format!(
+                        "dtype mismatch for op {}: {} is {} but earlier inputs are {}",
+                        spec.name, input.name, dtype, first
+                    ),
+                ));
+            }
+        }
+    }
+    let Some(input_dtype) = input_dtype else {
+        return Ok(());
+    };
+    let Some(output_dtype) = dtype_of(symbols, &op.output) else {
+        return Ok(());
+    };
+
+    let allowed: Vec<&Ident> = match spec.dtype {
+        DTypeRule::SameAsInputs => {
+            let mut allowed = vec![input_dtype];
+            for setting in settings.iter().filter(|setting| setting.name == "acc") {
+                if let OpAttrValue::DTypeList(acc) = &setting.value {
+                    allowed.extend(acc.iter());
+                }
+            }
+            allowed
+        }
+        DTypeRule::Cast => settings
+            .iter()
+            .filter(|setting| setting.name == "to")
+            .filter_map(|setting| match &setting.value {
+                OpAttrValue::Var(to) => Some(to),
+                _ => None,
+            })
+            .collect(),
+        DTypeRule::Bool => {
+            if output_dtype == "bool" {
+                return Ok(());
+            }
+            return Err(syn::Error::new(
+                op.output.span(),
+                format!(
+                    "dtype mismatch for op {}: output {} is {} but the op produces bool",
+                    spec.name, op.output, output_dtype
+                ),
+            ));
+        }
+    };
+    if allowed.contains(&output_dtype) {
+        return Ok(());
+    }
+    let expected: Vec<String> = allowed.iter().map(|dtype| dtype.to_string()).collect();
+    Err(syn::Error::new(
+        op.output.span(),
+        format!(
+            "dtype mismatch for op {}: output {} is {} but the op produces {}",
+            spec.name,
+            op.output,
+            output_dtype,
+            expected.join(" or ")
+        ),
+    ))
+}
+
+fn check_transfer(node: &TransferNode, symbols: &SymbolTable) -> syn::Result<()> {
+    let (Some(src), Some(dst)) = (
+        dtype_of(symbols, &node.src.name),
+        dtype_of(symbols, &node.dst.name),
+    ) else {
+        return Ok(());
+    };
+    if src == dst {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        node.dst.name.span(),
+        format!(
+            "transfer {} >> {} crosses dtypes {} -> {}; use op cast(..., to={}) first",
+            node.src.name, node.dst.name, src, dst, dst
+        ),
+    ))
+}
@@ -0,0 +1,68 @@
+use crate::diagnostics::Diagnostics;
+use crate::types::{GraphDsl, LoopNode, Node, RangeValue, Section};
+
+use super::shapes::sym_dim;
+
+/// Value of a loop bound that is known at compile time.
+pub(crate) fn range_constant(value: &RangeValue) -> Option<i64> {
+    match value {
+        RangeValue::Lit(lit) => lit.base10_parse().ok(),
+        RangeValue::Ident(_) => None,
+        RangeValue::Expr(dim) => sym_dim(dim).ok()?.as_constant(),
+    }
+}
+
+/// Check that loops with constant bounds step towards their end.
+pub(crate) fn check_loops(graph: &GraphDsl, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        if let Section::Block(block) = section {
+            check_nodes(&block.nodes, diagnostics);
+        }
+    }
+}
+
+fn check_nodes(nodes: &[Node], diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Loop(loop_node) => {
+                diagnostics.record(check_range(loop_node));
+                check_nodes(&loop_node.body, diagnostics);
+            }
+            Node::Call(call) => check_nodes(&call.inlined, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn check_range(loop_node: &LoopNode) -> syn::Result<()> {
+    let (Some(start), Some(end)) = (
+        range_constant(&loop_node.start),
+        range_constant(&loop_node.end),
+    ) else {
+        return Ok(());
+    };
+    let step = match &loop_node.step {
+        Some(step) => step.base10_parse::<i64>()?,
+        None => 1,
+    };
+    let dots = if loop_node.inclusive { "..=" } else { ".." };
+    if start > end && step > 0 {
+        return Err(syn::Error::new(
+            loop_node.name.span(),
+            format!(
+                "loop {} runs from {} down to {}; reversed ranges need a negative step, e.g. {}{}{} step -1",
+                loop_node.name, start, end, start, dots, end
+            ),
+        ));
+    }
+    if start < end && step < 0 {
+        return Err(syn::Error::new(
+            loop_node.name.span(),
+            format!(
+                "loop {} has step {} but its range {}{}{} is ascending",
+                loop_node.name, step, start, dots, end
+            ),
+        ));
+    }
+    Ok(())
+}
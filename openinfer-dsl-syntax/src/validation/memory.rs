@@ -0,0 +1,102 @@
+use proc_macro2::Span;
+use syn::Ident;
+
+use crate::types::{GraphDsl, InitValue, MemoryKindToken, Node, Section};
+
+use super::symbols::SymbolTable;
+use crate::diagnostics::Diagnostics;
+
+fn init_span(init: &InitValue) -> Span {
+    match init {
+        InitValue::Float { lit, .. } => lit.span(),
+        InitValue::Int { lit, .. } => lit.span(),
+        InitValue::Bool { lit } => lit.span(),
+    }
+}
+
+/// Check that every access respects the memory kind of its variable:
+/// constants are read-only, cache operations need persistent storage and
+/// `@init` is only accepted where the runtime honours it.
+pub(crate) fn check_memory(graph: &GraphDsl, symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        match section {
+            Section::Memory(mem) => {
+                if let MemoryKindToken::Dynamic = mem.kind {
+                    for var in &mem.vars {
+                        if let Some(init) = &var.init {
+                            diagnostics.push(syn::Error::new(
+                                init_span(init),
+                                format!(
+                                    "@init has no effect on dynamic variable {}; dynamic values are supplied by the caller",
+                                    var.name
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+            Section::Block(block) => check_nodes(&block.nodes, symbols, diagnostics),
+            Section::Dims(_) | Section::Func(_) => {}
+        }
+    }
+}
+
+fn check_nodes(nodes: &[Node], symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        let result = match node {
+            Node::Op(op) => check_write(&op.output, symbols),
+            Node::Transfer(node) => check_write(&node.dst.name, symbols),
+            Node::CacheRead(node) => {
+                diagnostics.record(check_cache(&node.src.name, "read", symbols));
+                check_write(&node.dst.name, symbols)
+            }
+            Node::CacheWrite(node) => check_cache(&node.dst.name, "write", symbols),
+            Node::CacheInc(node) => check_cache(&node.target, "increment", symbols),
+            Node::CacheDec(node) => check_cache(&node.target, "decrement", symbols),
+            Node::CacheReset(node) => check_cache(&node.target.name, "reset", symbols),
+            Node::Loop(loop_node) => {
+                check_nodes(&loop_node.body, symbols, diagnostics);
+                Ok(())
+            }
+            Node::Call(call) => {
+                check_nodes(&call.inlined, symbols, diagnostics);
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+        diagnostics.record(result);
+    }
+}
+
+fn check_write(name: &Ident, symbols: &SymbolTable) -> syn::Result<()> {
+    match symbols.get(name).and_then(|symbol| symbol.kind) {
+        Some(MemoryKindToken::Constant) => Err(syn::Error::new(
+            name.span(),
+            format!("cannot write to constant variable {}", name),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_cache(name: &Ident, verb: &str, symbols: &SymbolTable) -> syn::Result<()> {
+    let Some(symbol) = symbols.get(name) else {
+        return Ok(());
+    };
+    match symbol.kind {
+        Some(MemoryKindToken::Persistent) => Ok(()),
+        Some(kind) => Err(syn::Error::new(
+            name.span(),
+            format!(
+                "cache.{} requires a persistent variable, but {} is {}",
+                verb, name, kind
+            ),
+        )),
+        None => Err(syn::Error::new(
+            name.span(),
+            format!(
+                "cache.{} requires a persistent variable, but {} is an assign temporary",
+                verb, name
+            ),
+        )),
+    }
+}
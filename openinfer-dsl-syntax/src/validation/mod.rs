@@ -0,0 +1,32 @@
+//! Compile-time checks over a parsed graph.
+
+pub(crate) mod blocks;
+pub(crate) mod dims;
+pub(crate) mod dtypes;
+pub(crate) mod loops;
+pub(crate) mod memory;
+pub mod ops;
+pub mod shapes;
+pub(crate) mod symbols;
+pub(crate) mod tables;
+
+use crate::diagnostics::Diagnostics;
+use crate::types::GraphDsl;
+
+/// Run all compile-time checks over the parsed graph before any code is emitted.
+///
+/// Every pass runs even if an earlier one failed; all errors are combined.
+pub fn validate(graph: &GraphDsl) -> syn::Result<()> {
+    let mut diagnostics = Diagnostics::default();
+    blocks::check_blocks(graph, &mut diagnostics);
+    dims::check_dims(graph, &mut diagnostics);
+    loops::check_loops(graph, &mut diagnostics);
+    ops::check_ops(graph, &mut diagnostics);
+    let symbols = symbols::SymbolTable::build(graph, &mut diagnostics);
+    symbols.check_graph(graph, &mut diagnostics);
+    shapes::check_shapes(graph, &symbols, &mut diagnostics);
+    dtypes::check_dtypes(graph, &symbols, &mut diagnostics);
+    memory::check_memory(graph, &symbols, &mut diagnostics);
+    tables::check_tables(graph, &symbols, &mut diagnostics);
+    diagnostics.finish()
+}
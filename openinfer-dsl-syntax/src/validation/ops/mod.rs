@@ -0,0 +1,36 @@
+//! The built-in op registry and setting schemas.
+
+use crate::diagnostics::Diagnostics;
+use crate::types::{GraphDsl, Node, OpNode, Section};
+
+pub mod registry;
+pub mod schema;
+
+/// Check every op against the built-in registry: name, arity and settings.
+pub(crate) fn check_ops(graph: &GraphDsl, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        if let Section::Block(block) = section {
+            check_nodes(&block.nodes, diagnostics);
+        }
+    }
+}
+
+fn check_nodes(nodes: &[Node], diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Op(op) => {
+                diagnostics.record(check_op(op));
+            }
+            Node::Loop(loop_node) => check_nodes(&loop_node.body, diagnostics),
+            Node::Call(call) => check_nodes(&call.inlined, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn check_op(op: &OpNode) -> syn::Result<()> {
+    let spec = registry::lookup(&op.name)?;
+    spec.check_arity(&op.name, op.inputs.len())?;
+    schema::resolve(spec, &op.name, &op.settings)?;
+    Ok(())
+}
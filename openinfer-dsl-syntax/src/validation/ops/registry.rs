@@ -0,0 +1,243 @@
+//! Built-in ops and their static descriptions.
+
+use syn::Ident;
+
+use super::schema::{defaulted, optional, required, AttrKind, DefaultValue, SettingSpec};
+
+/// Number of positional inputs an op accepts.
+pub enum Arity {
+    /// Exactly this many inputs.
+    Exact(usize),
+    /// This many inputs or more.
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Exact(n) => count == *n,
+            Arity::AtLeast(n) => count >= *n,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Arity::Exact(1) => "1 input".to_string(),
+            Arity::Exact(n) => format!("{} inputs", n),
+            Arity::AtLeast(n) => format!("at least {} inputs", n),
+        }
+    }
+}
+
+/// How an op's output shape follows from its input shapes.
+#[derive(Clone, Copy)]
+pub enum ShapeRule {
+    /// Inputs broadcast against each other; the output has the broadcast shape.
+    Elementwise,
+    /// `[.., M, K] x [.., K, N] -> [.., M, N]`.
+    Matmul,
+    /// Drops (or keeps as 1) the dims listed in `axes`; all dims when omitted.
+    Reduce,
+    /// Inputs agree on every dim but `axis`, which is summed.
+    Concat,
+    /// The output has the input's shape; `axis` must be one of its dims.
+    Axis,
+}
+
+/// How an op's result dtype follows from its inputs and settings.
+#[derive(Clone, Copy)]
+pub enum DTypeRule {
+    /// All inputs share one dtype, which is also the result dtype. An `acc`
+    /// setting additionally allows the result to be any of the listed dtypes.
+    SameAsInputs,
+    /// The result dtype is given by the `to` setting.
+    Cast,
+    /// The result is always `bool`.
+    Bool,
+}
+
+/// Static description of a built-in op.
+pub struct OpSpec {
+    /// Name used in `op name(...)`.
+    pub name: &'static str,
+    /// Variant name of `openinfer::OpKind`.
+    pub kind: &'static str,
+    /// Accepted number of inputs.
+    pub arity: Arity,
+    /// Output shape rule.
+    pub shape: ShapeRule,
+    /// Result dtype rule.
+    pub dtype: DTypeRule,
+    /// Setting schema, in the order settings are emitted.
+    pub settings: &'static [SettingSpec],
+}
+
+/// Every built-in op.
+pub const OPS: &[OpSpec] = &[
+    OpSpec {
+        name: "add",
+        kind: "Add",
+        arity: Arity::Exact(2),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[optional("acc", AttrKind::DTypeList)],
+    },
+    OpSpec {
+        name: "sub",
+        kind: "Sub",
+        arity: Arity::Exact(2),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[optional("acc", AttrKind::DTypeList)],
+    },
+    OpSpec {
+        name: "mul",
+        kind: "Mul",
+        arity: Arity::Exact(2),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[optional("acc", AttrKind::DTypeList)],
+    },
+    OpSpec {
+        name: "div",
+        kind: "Div",
+        arity: Arity::Exact(2),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[],
+    },
+    OpSpec {
+        name: "abs",
+        kind: "Abs",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[],
+    },
+    OpSpec {
+        name: "neg",
+        kind: "Neg",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[],
+    },
+    OpSpec {
+        name: "relu",
+        kind: "Relu",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[
+            defaulted("alpha", AttrKind::Float, DefaultValue::Double(0.0)),
+            defaulted(
+                "clamp_max",
+                AttrKind::Float,
+                DefaultValue::Double(f64::INFINITY),
+            ),
+        ],
+    },
+    OpSpec {
+        name: "matmul",
+        kind: "Matmul",
+        arity: Arity::Exact(2),
+        shape: ShapeRule::Matmul,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[optional("acc", AttrKind::DTypeList)],
+    },
+    OpSpec {
+        name: "fill",
+        kind: "Fill",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[defaulted(
+            "value",
+            AttrKind::Float,
+            DefaultValue::Double(0.0),
+        )],
+    },
+    OpSpec {
+        name: "cast",
+        kind: "Cast",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::Cast,
+        settings: &[required("to", AttrKind::DType)],
+    },
+    OpSpec {
+        name: "sum",
+        kind: "Sum",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Reduce,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[
+            optional("axes", AttrKind::IntList),
+            defaulted("keepdims", AttrKind::Bool, DefaultValue::Bool(false)),
+            optional("acc", AttrKind::DTypeList),
+        ],
+    },
+    OpSpec {
+        name: "softmax",
+        kind: "Softmax",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Axis,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[defaulted("axis", AttrKind::Int, DefaultValue::Int(-1))],
+    },
+    OpSpec {
+        name: "concat",
+        kind: "Concat",
+        arity: Arity::AtLeast(2),
+        shape: ShapeRule::Concat,
+        dtype: DTypeRule::SameAsInputs,
+        settings: &[defaulted("axis", AttrKind::Int, DefaultValue::Int(0))],
+    },
+    OpSpec {
+        name: "is_finite",
+        kind: "IsFinite",
+        arity: Arity::Exact(1),
+        shape: ShapeRule::Elementwise,
+        dtype: DTypeRule::Bool,
+        settings: &[],
+    },
+];
+
+/// The built-in op called `name`.
+pub fn find(name: &Ident) -> Option<&'static OpSpec> {
+    OPS.iter().find(|spec| *name == spec.name)
+}
+
+/// Like [`find`], with an `unknown op` error and a suggestion.
+pub fn lookup(name: &Ident) -> syn::Result<&'static OpSpec> {
+    let key = name.to_string();
+    find(name).ok_or_else(|| {
+        syn::Error::new(
+            name.span(),
+            format!(
+                "unknown op: {}{}",
+                key,
+                crate::suggest::did_you_mean(&key, OPS.iter().map(|spec| spec.name))
+            ),
+        )
+    })
+}
+
+impl OpSpec {
+    /// Check the number of inputs of a use of this op.
+    pub fn check_arity(&self, name: &Ident, count: usize) -> syn::Result<()> {
+        if self.arity.accepts(count) {
+            Ok(())
+        } else {
+            Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "op {} expects {}, got {}",
+                    self.name,
+                    self.arity.describe(),
+                    count
+                ),
+            ))
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Setting schemas of the built-in ops and their resolution.
+
+use std::collections::HashMap;
+
+use syn::Ident;
+
+use crate::diagnostics::Diagnostics;
+use crate::dtype::check_dtype;
+use crate::types::{OpAttrValue, OpSetting};
+
+use super::registry::OpSpec;
+
+/// Expected shape of a setting value.
+#[derive(Clone, Copy, PartialEq)]
+pub enum AttrKind {
+    /// Float setting; integer literals are widened.
+    Float,
+    /// Integer setting.
+    Int,
+    /// `true` or `false`.
+    Bool,
+    /// `[0, 1]`.
+    IntList,
+    /// A dtype name such as `f32`.
+    DType,
+    /// `[i32, i64]`.
+    DTypeList,
+}
+
+impl AttrKind {
+    fn describe(self) -> &'static str {
+        match self {
+            AttrKind::Float => "a float",
+            AttrKind::Int => "an integer",
+            AttrKind::Bool => "a bool",
+            AttrKind::IntList => "an integer list (e.g. [0, 1])",
+            AttrKind::DType => "a dtype identifier",
+            AttrKind::DTypeList => "a dtype list (e.g. [i32, i64])",
+        }
+    }
+}
+
+/// Value used when a setting is omitted.
+#[derive(Clone, Copy)]
+pub enum DefaultValue {
+    /// Float default.
+    Double(f64),
+    /// Integer default.
+    Int(i64),
+    /// Bool default.
+    Bool(bool),
+}
+
+impl DefaultValue {
+    fn to_value(self) -> OpAttrValue {
+        match self {
+            DefaultValue::Double(value) => OpAttrValue::Double(value),
+            DefaultValue::Int(value) => OpAttrValue::Int(value),
+            DefaultValue::Bool(value) => OpAttrValue::Bool(value),
+        }
+    }
+}
+
+/// Whether a setting must be given.
+#[derive(Clone, Copy)]
+pub enum Presence {
+    /// The setting must be given.
+    Required,
+    /// The setting may be omitted and is then not emitted.
+    Optional,
+    /// The setting may be omitted and then takes this value.
+    Default(DefaultValue),
+}
+
+/// One entry of an op's setting schema.
+pub struct SettingSpec {
+    /// Setting name.
+    pub name: &'static str,
+    /// Expected value kind.
+    pub kind: AttrKind,
+    /// Whether the setting is required.
+    pub presence: Presence,
+}
+
+pub(crate) const fn required(name: &'static str, kind: AttrKind) -> SettingSpec {
+    SettingSpec {
+        name,
+        kind,
+        presence: Presence::Required,
+    }
+}
+
+pub(crate) const fn optional(name: &'static str, kind: AttrKind) -> SettingSpec {
+    SettingSpec {
+        name,
+        kind,
+        presence: Presence::Optional,
+    }
+}
+
+pub(crate) const fn defaulted(
+    name: &'static str,
+    kind: AttrKind,
+    default: DefaultValue,
+) -> SettingSpec {
+    SettingSpec {
+        name,
+        kind,
+        presence: Presence::Default(default),
+    }
+}
+
+/// A setting after schema resolution, in schema order.
+pub struct ResolvedSetting {
+    /// Setting name.
+    pub name: &'static str,
+    /// Kind from the schema.
+    pub kind: AttrKind,
+    /// Given or default value; integer floats are widened.
+    pub value: OpAttrValue,
+}
+
+struct SettingsMap {
+    settings: HashMap<String, OpSetting>,
+}
+
+impl SettingsMap {
+    /// Index `settings` by name, reporting every repeated one.
+    fn new(settings: &[OpSetting], diagnostics: &mut Diagnostics) -> Self {
+        let mut map = HashMap::new();
+        for setting in settings {
+            let key = setting.name.to_string();
+            if map.contains_key(&key) {
+                diagnostics.push(syn::Error::new(
+                    setting.name.span(),
+                    format!("duplicate setting: {}", key),
+                ));
+                continue;
+            }
+            map.insert(key, setting.clone());
+        }
+        Self { settings: map }
+    }
+
+    fn take(&mut self, name: &str) -> Option<OpSetting> {
+        self.settings.remove(name)
+    }
+
+    /// Report every setting the schema did not take.
+    fn ensure_empty(self, spec: &OpSpec, diagnostics: &mut Diagnostics) {
+        let mut leftover: Vec<OpSetting> = self.settings.into_values().collect();
+        leftover.sort_by_key(|setting| setting.name.to_string());
+        for setting in leftover {
+            let key = setting.name.to_string();
+            diagnostics.push(syn::Error::new(
+                setting.name.span(),
+                format!(
+                    "unsupported setting for op {}: {}{}",
+                    spec.name,
+                    key,
+                    crate::suggest::did_you_mean(
+                        &key,
+                        spec.settings.iter().map(|setting| setting.name)
+                    )
+                ),
+            ));
+        }
+    }
+}
+
+/// Validate `settings` against the op's schema and fill in defaults.
+pub fn resolve(
+    spec: &OpSpec,
+    op: &Ident,
+    settings: &[OpSetting],
+) -> syn::Result<Vec<ResolvedSetting>> {
+    let mut diagnostics = Diagnostics::default();
+    let mut map = SettingsMap::new(settings, &mut diagnostics);
+    let mut resolved = Vec::new();
+    for setting_spec in spec.settings {
+        let value = match map.take(setting_spec.name) {
+            Some(setting) => match diagnostics.record(check_kind(spec, setting_spec, &setting)) {
+                Some(value) => value,
+                None => continue,
+            },
+            None => match setting_spec.presence {
+                Presence::Required => {
+                    diagnostics.push(syn::Error::new(
+                        op.span(),
+                        format!(
+                            "missing required setting for op {}: {}",
+                            spec.name, setting_spec.name
+                        ),
+                    ));
+                    continue;
+                }
+                Presence::Optional => continue,
+                Presence::Default(default) => default.to_value(),
+            },
+        };
+        resolved.push(ResolvedSetting {
+            name: setting_spec.name,
+            kind: setting_spec.kind,
+            value,
+        });
+    }
+    map.ensure_empty(spec, &mut diagnostics);
+    diagnostics.finish()?;
+    Ok(resolved)
+}
+
+fn check_kind(
+    spec: &OpSpec,
+    setting_spec: &SettingSpec,
+    setting: &OpSetting,
+) -> syn::Result<OpAttrValue> {
+    let value = match (setting_spec.kind, &setting.value) {
+        (AttrKind::Float, OpAttrValue::Double(_)) => Some(setting.value.clone()),
+        (AttrKind::Float, OpAttrValue::Int(value)) => Some(OpAttrValue::Double(*value as f64)),
+        (AttrKind::Int, OpAttrValue::Int(_))
+        | (AttrKind::Bool, OpAttrValue::Bool(_))
+        | (AttrKind::IntList, OpAttrValue::IntList(_)) => Some(setting.value.clone()),
+        (AttrKind::DType, OpAttrValue::Var(ident)) => {
+            check_dtype(ident)?;
+            Some(setting.value.clone())
+        }
+        (AttrKind::DTypeList, OpAttrValue::DTypeList(dtypes)) => {
+            let mut diagnostics = Diagnostics::default();
+            for dtype in dtypes {
+                diagnostics.record(check_dtype(dtype));
+            }
+            diagnostics.finish()?;
+            Some(setting.value.clone())
+        }
+        _ => None,
+    };
+    value.ok_or_else(|| {
+        syn::Error::new(
+            setting.name.span(),
+            format!(
+                "setting {} of op {} expects {}",
+                setting_spec.name,
+                spec.name,
+                setting_spec.kind.describe()
+            ),
+        )
+    })
+}
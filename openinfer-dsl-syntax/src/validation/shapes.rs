@@ -0,0 +1,497 @@
+//! Symbolic shapes and shape inference.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use syn::Ident;
+
+use crate::types::{Dim, DimOp, GraphDsl, Node, OpAttrValue, OpNode, Section};
+
+use super::ops::registry::{self, OpSpec, ShapeRule};
+use super::ops::schema::{self, ResolvedSetting};
+use super::symbols::SymbolTable;
+use crate::diagnostics::Diagnostics;
+
+/// A dimension in normal form: a polynomial over symbolic dims.
+///
+/// Each term maps a sorted list of symbols to its integer coefficient; the
+/// constant term uses the empty list. `B*D`, `D*B` and `1*B*D` all normalise
+/// to the same value, so structural equality is symbolic equality.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SymDim {
+    terms: BTreeMap<Vec<String>, i64>,
+}
+
+impl SymDim {
+    /// The constant `value`.
+    pub fn constant(value: i64) -> Self {
+        Self::from_term(Vec::new(), value)
+    }
+
+    /// The symbolic dim `name`.
+    pub fn symbol(name: &str) -> Self {
+        Self::from_term(vec![name.to_string()], 1)
+    }
+
+    fn from_term(symbols: Vec<String>, coefficient: i64) -> Self {
+        let mut terms = BTreeMap::new();
+        if coefficient != 0 {
+            terms.insert(symbols, coefficient);
+        }
+        Self { terms }
+    }
+
+    /// `self + other`, or `None` if a coefficient overflows.
+    pub fn add(&self, other: &SymDim) -> Option<SymDim> {
+        let mut terms = self.terms.clone();
+        for (symbols, coefficient) in &other.terms {
+            let sum = terms.entry(symbols.clone()).or_insert(0);
+            *sum = sum.checked_add(*coefficient)?;
+        }
+        terms.retain(|_, coefficient| *coefficient != 0);
+        Some(SymDim { terms })
+    }
+
+    /// `self - other`, or `None` if a coefficient overflows.
+    pub fn sub(&self, other: &SymDim) -> Option<SymDim> {
+        self.add(&other.mul(&SymDim::constant(-1))?)
+    }
+
+    /// `self * other`, or `None` if a coefficient overflows.
+    pub fn mul(&self, other: &SymDim) -> Option<SymDim> {
+        let mut out = SymDim::constant(0);
+        for (left, left_coefficient) in &self.terms {
+            for (right, right_coefficient) in &other.terms {
+                let mut symbols = left.clone();
+                symbols.extend(right.iter().cloned());
+                symbols.sort();
+                out = out.add(&SymDim::from_term(
+                    symbols,
+                    left_coefficient.checked_mul(*right_coefficient)?,
+                ))?;
+            }
+        }
+        Some(out)
+    }
+
+    fn is_one(&self) -> bool {
+        *self == SymDim::constant(1)
+    }
+
+    /// The value of a dim without symbols.
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.terms.iter().next() {
+            None => Some(0),
+            Some((symbols, value)) if self.terms.len() == 1 && symbols.is_empty() => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// A single symbol or constant, which needs no parentheses when nested.
+    fn is_atom(&self) -> bool {
+        self.as_constant().is_some()
+            || (self.terms.len() == 1 && self.terms.iter().all(|(s, c)| *c == 1 && s.len() == 1))
+    }
+
+    /// Floor (or ceil) division; folds constants and exact divisions and
+    /// otherwise produces an opaque `floor(a/b)` / `ceil(a/b)` symbol.
+    fn div(&self, den: &SymDim, ceil: bool, den_dim: &Dim) -> syn::Result<SymDim> {
+        if let Some(d) = den.as_constant() {
+            if d == 0 {
+                return Err(syn::Error::new(
+                    den_dim.span(),
+                    "division by zero in dimension expression",
+                ));
+            }
+            if let Some(n) = self.as_constant() {
+                let value = if ceil {
+                    n.checked_neg()
+                        .and_then(|n| floor_div(n, d))
+                        .and_then(i64::checked_neg)
+                } else {
+                    floor_div(n, d)
+                };
+                return value.map(SymDim::constant).ok_or_else(|| overflow(den_dim));
+            }
+            if self
+                .terms
+                .values()
+                .all(|coefficient| coefficient.checked_rem(d) == Some(0))
+            {
+                let terms = self
+                    .terms
+                    .iter()
+                    .map(|(symbols, coefficient)| {
+                        Some((symbols.clone(), coefficient.checked_div(d)?))
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| overflow(den_dim))?;
+                return Ok(SymDim { terms });
+            }
+        }
+        let wrap = |dim: &SymDim| {
+            if dim.is_atom() {
+                dim.to_string()
+            } else {
+                format!("({})", dim)
+            }
+        };
+        let name = format!(
+            "{}({}/{})",
+            if ceil { "ceil" } else { "floor" },
+            wrap(self),
+            wrap(den)
+        );
+        Ok(SymDim::symbol(&name))
+    }
+}
+
+fn floor_div(n: i64, d: i64) -> Option<i64> {
+    let q = n.checked_div(d)?;
+    if n % d != 0 && ((n < 0) != (d < 0)) {
+        q.checked_sub(1)
+    } else {
+        Some(q)
+    }
+}
+
+fn overflow(dim: &Dim) -> syn::Error {
+    syn::Error::new(dim.span(), "dimension expression overflows i64")
+}
+
+impl fmt::Display for SymDim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        // Symbolic terms first, constant last: `B*D+1` rather than `1+B*D`.
+        let ordered = self
+            .terms
+            .iter()
+            .filter(|(symbols, _)| !symbols.is_empty())
+            .chain(self.terms.iter().filter(|(symbols, _)| symbols.is_empty()));
+        for (i, (symbols, coefficient)) in ordered.enumerate() {
+            let magnitude = coefficient.unsigned_abs();
+            if i == 0 {
+                if *coefficient < 0 {
+                    write!(f, "-")?;
+                }
+            } else if *coefficient < 0 {
+                write!(f, "-")?;
+            } else {
+                write!(f, "+")?;
+            }
+            let mut factors = Vec::new();
+            if magnitude != 1 || symbols.is_empty() {
+                factors.push(magnitude.to_string());
+            }
+            factors.extend(symbols.iter().cloned());
+            write!(f, "{}", factors.join("*"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalised dims of a tensor, outermost first.
+pub type Shape = Vec<SymDim>;
+
+/// Normalise `dim`, keeping every symbol symbolic.
+pub fn sym_dim(dim: &Dim) -> syn::Result<SymDim> {
+    sym_dim_with(dim, &|ident| Ok(SymDim::symbol(&ident.to_string())))
+}
+
+/// Normalise `dim`, resolving each symbol through `symbol`.
+pub fn sym_dim_with(
+    dim: &Dim,
+    symbol: &dyn Fn(&Ident) -> syn::Result<SymDim>,
+) -> syn::Result<SymDim> {
+    Ok(match dim {
+        Dim::Ident(ident) => symbol(ident)?,
+        Dim::Lit(lit) => SymDim::constant(lit.base10_parse()?),
+        Dim::Binary { op, left, right } => {
+            let l = sym_dim_with(left, symbol)?;
+            let r = sym_dim_with(right, symbol)?;
+            match op {
+                DimOp::Add => l.add(&r).ok_or_else(|| overflow(dim))?,
+                DimOp::Sub => l.sub(&r).ok_or_else(|| overflow(dim))?,
+                DimOp::Mul => l.mul(&r).ok_or_else(|| overflow(dim))?,
+                DimOp::Div => l.div(&r, false, right)?,
+            }
+        }
+        Dim::CeilDiv { num, den } => {
+            sym_dim_with(num, symbol)?.div(&sym_dim_with(den, symbol)?, true, den)?
+        }
+    })
+}
+
+/// Normalise every dim of a declaration.
+pub fn shape_of(dims: &[Dim]) -> syn::Result<Shape> {
+    dims.iter().map(sym_dim).collect()
+}
+
+/// Format a shape as `[B, 2*D]`.
+pub fn format_shape(shape: &[SymDim]) -> String {
+    let items: Vec<String> = shape.iter().map(|dim| dim.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Infer the output shape of every op and compare it with the declared
+/// shapes of its inputs and output variable. Declared dims that cannot be
+/// normalised (e.g. division by zero) are reported once, here.
+pub(crate) fn check_shapes(graph: &GraphDsl, symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        match section {
+            Section::Memory(mem) => {
+                for var in &mem.vars {
+                    diagnostics.record(shape_of(&var.dims));
+                }
+            }
+            Section::Block(block) => check_nodes(&block.nodes, symbols, diagnostics),
+            Section::Dims(_) | Section::Func(_) => {}
+        }
+    }
+}
+
+fn check_nodes(nodes: &[Node], symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Assign(assign) => {
+                diagnostics.record(shape_of(&assign.dims));
+            }
+            Node::Op(op) => {
+                diagnostics.record(check_op(op, symbols));
+            }
+            Node::Loop(loop_node) => check_nodes(&loop_node.body, symbols, diagnostics),
+            Node::Call(call) => check_nodes(&call.inlined, symbols, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn check_op(op: &OpNode, symbols: &SymbolTable) -> syn::Result<()> {
+    // Unknown ops and malformed settings are reported by the ops pass.
+    let Some(spec) = registry::find(&op.name) else {
+        return Ok(());
+    };
+    let Ok(settings) = schema::resolve(spec, &op.name, &op.settings) else {
+        return Ok(());
+    };
+    if spec.check_arity(&op.name, op.inputs.len()).is_err() {
+        return Ok(());
+    }
+    let mut inputs = Vec::new();
+    for input in &op.inputs {
+        match symbols.get(&input.name) {
+            Some(symbol) => match shape_of(symbol.dims) {
+                Ok(shape) => inputs.push((&input.name, shape)),
+                Err(_) => return Ok(()),
+            },
+            None => return Ok(()),
+        }
+    }
+    let inferred = infer(spec, &op.name, &inputs, &settings)?;
+    let Some(output) = symbols.get(&op.output) else {
+        return Ok(());
+    };
+    let Ok(declared) = shape_of(output.dims) else {
+        return Ok(());
+    };
+    if declared != inferred {
+        return Err(syn::Error::new(
+            op.output.span(),
+            format!(
+                "shape mismatch for op {}: output {} is declared {} but inferred {}",
+                spec.name,
+                op.output,
+                format_shape(&declared),
+                format_shape(&inferred)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn infer(
+    spec: &OpSpec,
+    op: &Ident,
+    inputs: &[(&Ident, Shape)],
+    settings: &[ResolvedSetting],
+) -> syn::Result<Shape> {
+    match spec.shape {
+        ShapeRule::Elementwise => {
+            let mut out = inputs[0].1.clone();
+            for (name, shape) in &inputs[1..] {
+                out = broadcast(&out, shape).ok_or_else(|| {
+                    syn::Error::new(
+                        name.span(),
+                        format!(
+                            "shape mismatch for op {}: cannot broadcast {} {} against {}",
+                            spec.name,
+                            name,
+                            format_shape(shape),
+                            format_shape(&out)
+                        ),
+                    )
+                })?;
+            }
+            Ok(out)
+        }
+        ShapeRule::Matmul => {
+            let (left_name, left) = &inputs[0];
+            let (right_name, right) = &inputs[1];
+            for (name, shape) in [(left_name, left), (right_name, right)] {
+                if shape.len() < 2 {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!(
+                            "op {} expects inputs of rank >= 2, {} has {}",
+                            spec.name,
+                            name,
+                            format_shape(shape)
+                        ),
+                    ));
+                }
+            }
+            let inner_left = &left[left.len() - 1];
+            let inner_right = &right[right.len() - 2];
+            if inner_left != inner_right {
+                return Err(syn::Error::new(
+                    right_name.span(),
+                    format!(
+                        "shape mismatch for op {}: inner dims {} of {} and {} of {} differ",
+                        spec.name, inner_left, left_name, inner_right, right_name
+                    ),
+                ));
+            }
+            let batch = broadcast(&left[..left.len() - 2], &right[..right.len() - 2])
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        right_name.span(),
+                        format!(
+                            "shape mismatch for op {}: cannot broadcast batch dims of {} {} and {} {}",
+                            spec.name,
+                            left_name,
+                            format_shape(left),
+                            right_name,
+                            format_shape(right)
+                        ),
+                    )
+                })?;
+            let mut out = batch;
+            out.push(left[left.len() - 2].clone());
+            out.push(right[right.len() - 1].clone());
+            Ok(out)
+        }
+        ShapeRule::Reduce => {
+            let shape = &inputs[0].1;
+            let keepdims = matches!(setting(settings, "keepdims"), Some(OpAttrValue::Bool(true)));
+            let axes = match setting(settings, "axes") {
+                Some(OpAttrValue::IntList(axes)) => axes
+                    .iter()
+                    .map(|axis| normalize_axis(spec, op, *axis, shape.len()))
+                    .collect::<syn::Result<Vec<_>>>()?,
+                _ => (0..shape.len()).collect(),
+            };
+            Ok(shape
+                .iter()
+                .enumerate()
+                .filter_map(|(i, dim)| {
+                    if !axes.contains(&i) {
+                        Some(dim.clone())
+                    } else if keepdims {
+                        Some(SymDim::constant(1))
+                    } else {
+                        None
+                    }
+                })
+                .collect())
+        }
+        ShapeRule::Axis => {
+            let shape = &inputs[0].1;
+            if let Some(OpAttrValue::Int(axis)) = setting(settings, "axis") {
+                normalize_axis(spec, op, *axis, shape.len())?;
+            }
+            Ok(shape.clone())
+        }
+        ShapeRule::Concat => {
+            let (_, first) = &inputs[0];
+            let axis = match setting(settings, "axis") {
+                Some(OpAttrValue::Int(axis)) => normalize_axis(spec, op, *axis, first.len())?,
+                _ => 0,
+            };
+            let mut out = first.clone();
+            for (name, shape) in &inputs[1..] {
+                let compatible = shape.len() == out.len()
+                    && shape
+                        .iter()
+                        .zip(&out)
+                        .enumerate()
+                        .all(|(i, (dim, acc))| i == axis || dim == acc);
+                if !compatible {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!(
+                            "shape mismatch for op {}: {} {} does not match {} outside axis {}",
+                            spec.name,
+                            name,
+                            format_shape(shape),
+                            format_shape(first),
+                            axis
+                        ),
+                    ));
+                }
+                out[axis] = out[axis].add(&shape[axis]).ok_or_else(|| {
+                    syn::Error::new(
+                        name.span(),
+                        format!(
+                            "concatenated axis {} of op {} overflows i64",
+                            axis, spec.name
+                        ),
+                    )
+                })?;
+            }
+            Ok(out)
+        }
+    }
+}
+
+fn setting<'s>(settings: &'s [ResolvedSetting], name: &str) -> Option<&'s OpAttrValue> {
+    settings
+        .iter()
+        .find(|setting| setting.name == name)
+        .map(|setting| &setting.value)
+}
+
+fn normalize_axis(spec: &OpSpec, op: &Ident, axis: i64, rank: usize) -> syn::Result<usize> {
+    let resolved = if axis < 0 { axis + rank as i64 } else { axis };
+    if resolved < 0 || resolved >= rank as i64 {
+        return Err(syn::Error::new(
+            op.span(),
+            format!(
+                "axis {} is out of range for op {} on a rank {} input",
+                axis, spec.name, rank
+            ),
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+/// Numpy-style broadcast: trailing dims must match or be the literal 1.
+fn broadcast(left: &[SymDim], right: &[SymDim]) -> Option<Shape> {
+    let rank = left.len().max(right.len());
+    let mut out = Vec::with_capacity(rank);
+    for i in 0..rank {
+        let l = (i + left.len()).checked_sub(rank).map(|i| &left[i]);
+        let r = (i + right.len()).checked_sub(rank).map(|i| &right[i]);
+        let dim = match (l, r) {
+            (Some(l), Some(r)) if l == r => l,
+            (Some(l), Some(r)) if r.is_one() => l,
+            (Some(l), Some(r)) if l.is_one() => r,
+            (Some(_), Some(_)) => return None,
+            (Some(dim), None) | (None, Some(dim)) => dim,
+            (None, None) => unreachable!(),
+        };
+        out.push(dim.clone());
+    }
+    Some(out)
+}
@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet};
+
+use syn::Ident;
+
+use crate::diagnostics::Diagnostics;
+use crate::suggest;
+
+use crate::types::{
+    CacheAccess, CacheIndexExpr, CacheIndexValue, Dim, GraphDsl, IndexExpr, MemoryKindToken, Node,
+    Section, VarRef,
+};
+
+/// Declaration site of a variable.
+pub(crate) struct Symbol<'a> {
+    pub(crate) name: &'a Ident,
+    /// Memory section of the declaration; `None` for `assign` temporaries.
+    pub(crate) kind: Option<&'a MemoryKindToken>,
+    pub(crate) dtype: &'a Ident,
+    pub(crate) dims: &'a [Dim],
+    /// Prefix table indices, e.g. `i, j` for `state(i, j)`.
+    pub(crate) table_indices: &'a [Ident],
+    /// Block of an `assign` temporary; `None` for memory section variables.
+    pub(crate) block: Option<&'a Ident>,
+}
+
+/// What a node can see while a block is walked in order.
+struct Scope<'n> {
+    block: &'n Ident,
+    /// Indices of the enclosing loops.
+    indices: Vec<&'n Ident>,
+    /// Temporaries assigned so far in the block.
+    assigned: HashSet<String>,
+}
+
+/// Every variable name visible to the graph body.
+///
+/// Memory section declarations and `assign` temporaries share one namespace,
+/// because both end up as variables of the same runtime `Graph`.
+pub(crate) struct SymbolTable<'a> {
+    vars: HashMap<String, Symbol<'a>>,
+}
+
+impl<'a> SymbolTable<'a> {
+    pub(crate) fn build(graph: &'a GraphDsl, diagnostics: &mut Diagnostics) -> Self {
+        let mut table = Self {
+            vars: HashMap::new(),
+        };
+        for section in &graph.sections {
+            match section {
+                Section::Memory(mem) => {
+                    for var in &mem.vars {
+                        table.declare(
+                            Symbol {
+                                name: &var.name,
+                                kind: Some(&mem.kind),
+                                dtype: &var.dtype,
+                                dims: &var.dims,
+                                table_indices: &var.table_indices,
+                                block: None,
+                            },
+                            diagnostics,
+                        );
+                    }
+                }
+                Section::Block(block) => {
+                    table.declare_assigns(&block.name, &block.nodes, diagnostics)
+                }
+                Section::Dims(_) | Section::Func(_) => {}
+            }
+        }
+        table
+    }
+
+    pub(crate) fn contains(&self, name: &Ident) -> bool {
+        self.vars.contains_key(&name.to_string())
+    }
+
+    pub(crate) fn get(&self, name: &Ident) -> Option<&Symbol<'a>> {
+        self.vars.get(&name.to_string())
+    }
+
+    fn declare(&mut self, symbol: Symbol<'a>, diagnostics: &mut Diagnostics) {
+        let key = symbol.name.to_string();
+        if self.vars.contains_key(&key) {
+            diagnostics.push(syn::Error::new(
+                symbol.name.span(),
+                format!("duplicate variable: {}", key),
+            ));
+            return;
+        }
+        self.vars.insert(key, symbol);
+    }
+
+    fn declare_assigns(
+        &mut self,
+        block: &'a Ident,
+        nodes: &'a [Node],
+        diagnostics: &mut Diagnostics,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Assign(assign) => self.declare(
+                    Symbol {
+                        name: &assign.name,
+                        kind: None,
+                        dtype: &assign.dtype,
+                        dims: &assign.dims,
+                        table_indices: &[],
+                        block: Some(block),
+                    },
+                    diagnostics,
+                ),
+                Node::Loop(loop_node) => self.declare_assigns(block, &loop_node.body, diagnostics),
+                Node::Call(call) => self.declare_assigns(block, &call.inlined, diagnostics),
+                _ => {}
+            }
+        }
+    }
+
+    /// Resolve every variable reference in every block.
+    ///
+    /// An `assign` temporary is in scope from its `assign` to the end of its
+    /// block, including loop bodies and inlined calls in between.
+    pub(crate) fn check_graph(&self, graph: &GraphDsl, diagnostics: &mut Diagnostics) {
+        for section in &graph.sections {
+            if let Section::Block(block) = section {
+                let mut scope = Scope {
+                    block: &block.name,
+                    indices: Vec::new(),
+                    assigned: HashSet::new(),
+                };
+                self.check_nodes(&block.nodes, &mut scope, diagnostics);
+            }
+        }
+    }
+
+    fn check_nodes<'n>(
+        &self,
+        nodes: &'n [Node],
+        scope: &mut Scope<'n>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        for node in nodes {
+            match node {
+                Node::Loop(loop_node) => {
+                    scope.indices.push(&loop_node.index);
+                    self.check_nodes(&loop_node.body, scope, diagnostics);
+                    scope.indices.pop();
+                }
+                Node::Call(call) => self.check_nodes(&call.inlined, scope, diagnostics),
+                Node::Assign(assign) => {
+                    scope.assigned.insert(assign.name.to_string());
+                }
+                _ => self.check_node(node, scope, diagnostics),
+            }
+        }
+    }
+
+    fn check_node(&self, node: &Node, scope: &Scope, diagnostics: &mut Diagnostics) {
+        match node {
+            Node::Op(op) => {
+                for input in &op.inputs {
+                    diagnostics.record(self.check_var_ref(input, scope));
+                }
+                diagnostics.record(self.check_var(&op.output, scope));
+            }
+            Node::Branch(branch) => {
+                if let Some(cond) = &branch.cond {
+                    diagnostics.record(self.check_var(cond, scope));
+                }
+            }
+            Node::CacheRead(node) => {
+                diagnostics.record(self.check_cache_access(&node.src, scope));
+                diagnostics.record(self.check_var_ref(&node.dst, scope));
+            }
+            Node::CacheWrite(node) => {
+                diagnostics.record(self.check_var_ref(&node.src, scope));
+                diagnostics.record(self.check_cache_access(&node.dst, scope));
+            }
+            Node::CacheInc(node) => {
+                diagnostics.record(self.check_var(&node.target, scope));
+            }
+            Node::CacheDec(node) => {
+                diagnostics.record(self.check_var(&node.target, scope));
+            }
+            Node::CacheReset(node) => {
+                diagnostics.record(self.check_cache_access(&node.target, scope));
+            }
+            Node::Transfer(node) => {
+                diagnostics.record(self.check_var_ref(&node.src, scope));
+                diagnostics.record(self.check_var_ref(&node.dst, scope));
+            }
+            Node::Yield(node) => {
+                for var in &node.vars {
+                    diagnostics.record(self.check_var(var, scope));
+                }
+            }
+            Node::Await(node) => {
+                for var in &node.vars {
+                    diagnostics.record(self.check_var(var, scope));
+                }
+            }
+            Node::Loop(_)
+            | Node::Call(_)
+            | Node::Assign(_)
+            | Node::Barrier
+            | Node::Dep(_)
+            | Node::Return => {}
+        }
+    }
+
+    fn check_var(&self, name: &Ident, scope: &Scope) -> syn::Result<()> {
+        let key = name.to_string();
+        match self.get(name) {
+            Some(symbol) if symbol.kind.is_some() || scope.assigned.contains(&key) => Ok(()),
+            Some(symbol) => Err(syn::Error::new(
+                name.span(),
+                if symbol.block == Some(scope.block) {
+                    format!("variable {} is used before it is assigned", key)
+                } else {
+                    format!("variable {} is not assigned in block {}", key, scope.block)
+                },
+            )),
+            None => Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "unknown variable: {}{}",
+                    key,
+                    suggest::did_you_mean(&key, self.vars.keys().map(String::as_str))
+                ),
+            )),
+        }
+    }
+
+    /// Index identifiers may name an enclosing loop index or a scalar variable.
+    fn check_index(&self, name: &Ident, scope: &Scope) -> syn::Result<()> {
+        if scope.indices.contains(&name) {
+            return Ok(());
+        }
+        if self.contains(name) {
+            return self.check_var(name, scope);
+        }
+        let key = name.to_string();
+        let indices: Vec<String> = scope
+            .indices
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        let candidates = indices.iter().chain(self.vars.keys()).map(String::as_str);
+        Err(syn::Error::new(
+            name.span(),
+            format!(
+                "unknown variable: {}{}",
+                key,
+                suggest::did_you_mean(&key, candidates)
+            ),
+        ))
+    }
+
+    fn check_var_ref(&self, var_ref: &VarRef, scope: &Scope) -> syn::Result<()> {
+        self.check_var(&var_ref.name, scope)?;
+        for index in &var_ref.indices {
+            if let IndexExpr::Ident(ident) = index {
+                self.check_index(ident, scope)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_cache_access(&self, access: &CacheAccess, scope: &Scope) -> syn::Result<()> {
+        self.check_var(&access.name, scope)?;
+        for index in &access.indices {
+            let values = match index {
+                CacheIndexExpr::Single(value) => [Some(value), None],
+                CacheIndexExpr::Slice { start, end } => [start.as_ref(), end.as_ref()],
+            };
+            for value in values.into_iter().flatten() {
+                if let CacheIndexValue::Ident(ident) = value {
+                    self.check_index(ident, scope)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use syn::Ident;
+
+use crate::types::{CacheAccess, GraphDsl, Node, Section, VarDecl, VarRef};
+
+use super::symbols::SymbolTable;
+use crate::diagnostics::Diagnostics;
+
+/// Check prefix-table declarations and that every indexed access fits the
+/// table it addresses.
+pub(crate) fn check_tables(graph: &GraphDsl, symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for section in &graph.sections {
+        match section {
+            Section::Memory(mem) => {
+                for var in &mem.vars {
+                    diagnostics.record(check_decl(var));
+                }
+            }
+            Section::Block(block) => check_nodes(&block.nodes, symbols, diagnostics),
+            Section::Dims(_) | Section::Func(_) => {}
+        }
+    }
+}
+
+fn check_decl(var: &VarDecl) -> syn::Result<()> {
+    let mut indices = HashSet::new();
+    for index in &var.table_indices {
+        if !indices.insert(index.to_string()) {
+            return Err(syn::Error::new(
+                index.span(),
+                format!("duplicate table index {} on {}", index, var.name),
+            ));
+        }
+    }
+    if var.table && var.table_indices.is_empty() {
+        return Err(syn::Error::new(
+            var.name.span(),
+            format!(
+                "@table variable {} must declare indices, e.g. {}(i)",
+                var.name, var.name
+            ),
+        ));
+    }
+    let mut seen = HashSet::new();
+    let annotated = var
+        .auto_dim
+        .iter()
+        .map(|index| ("@auto_dim", index))
+        .chain(var.fixed.iter().map(|(index, _)| ("@fixed", index)));
+    for (attr, index) in annotated {
+        let key = index.to_string();
+        if !indices.contains(&key) {
+            return Err(syn::Error::new(
+                index.span(),
+                format!(
+                    "{} index {} is not declared in the table indices of {}",
+                    attr, index, var.name
+                ),
+            ));
+        }
+        if !seen.insert(key) {
+            return Err(syn::Error::new(
+                index.span(),
+                format!(
+                    "table index {} of {} is listed more than once in @auto_dim/@fixed",
+                    index, var.name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_nodes(nodes: &[Node], symbols: &SymbolTable, diagnostics: &mut Diagnostics) {
+    for node in nodes {
+        match node {
+            Node::Op(op) => {
+                for input in &op.inputs {
+                    diagnostics.record(check_var_ref(input, symbols));
+                }
+            }
+            Node::CacheRead(node) => {
+                diagnostics.record(check_cache_access(&node.src, symbols));
+                diagnostics.record(check_var_ref(&node.dst, symbols));
+            }
+            Node::CacheWrite(node) => {
+                diagnostics.record(check_var_ref(&node.src, symbols));
+                diagnostics.record(check_cache_access(&node.dst, symbols));
+            }
+            Node::CacheReset(node) => {
+                diagnostics.record(check_cache_access(&node.target, symbols));
+            }
+            Node::Transfer(node) => {
+                diagnostics.record(check_var_ref(&node.src, symbols));
+                diagnostics.record(check_var_ref(&node.dst, symbols));
+            }
+            Node::Loop(loop_node) => check_nodes(&loop_node.body, symbols, diagnostics),
+            Node::Call(call) => check_nodes(&call.inlined, symbols, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn check_var_ref(var_ref: &VarRef, symbols: &SymbolTable) -> syn::Result<()> {
+    check_arity(&var_ref.name, var_ref.indices.len(), symbols)
+}
+
+fn check_cache_access(access: &CacheAccess, symbols: &SymbolTable) -> syn::Result<()> {
+    check_arity(&access.name, access.indices.len(), symbols)
+}
+
+fn check_arity(name: &Ident, used: usize, symbols: &SymbolTable) -> syn::Result<()> {
+    let Some(symbol) = symbols.get(name) else {
+        return Ok(());
+    };
+    let declared = symbol.table_indices.len();
+    if used <= declared {
+        return Ok(());
+    }
+    let message = if declared == 0 {
+        format!("{} is not a table and cannot be indexed", name)
+    } else {
+        format!(
+            "too many indices for {}: table declares {} ({}), got {}",
+            name,
+            declared,
+            symbol
+                .table_indices
+                .iter()
+                .map(|index| index.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            used
+        )
+    };
+    Err(syn::Error::new(name.span(), message))
+}
@@ -0,0 +1,717 @@
+use crate::types::{GraphDsl, OpAttrValue};
+use crate::validation::ops::{registry, schema};
+use crate::validation::shapes;
+use syn::parse::Parser;
+use crate::validation::validate;
+use syn::parse_str;
+
+fn validate_src(src: &str) -> syn::Result<()> {
+    let graph = parse_str::<GraphDsl>(src).expect("parse graph");
+    validate(&graph)
+}
+
+fn validate_err(src: &str) -> String {
+    validate_src(src)
+        .expect_err("expected validation error")
+        .to_string()
+}
+
+#[test]
+fn resolves_declared_and_assigned_variables() {
+    validate_src(
+        r#"
+        dynamic { x: f32[B]; }
+        constant { w: f32[B]; }
+        persistent { state(i): f32[B] @table; }
+        block entry {
+            assign t0: f32[B];
+            op add(x, w) >> t0;
+            loop l (idx in 0..4) {
+                cache.write t0 >> state[idx];
+                cache.read state[idx] >> t0;
+            }
+            yield t0;
+            return;
+        }
+        "#,
+    )
+    .expect("valid graph");
+}
+
+#[test]
+fn rejects_unknown_variable_references() {
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B]; y: f32[B]; }
+        block entry {
+            op add(x, yy) >> x;
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("unknown variable: yy"));
+
+    let err = validate_err(
+        r#"
+        persistent { state(i): f32 @table; }
+        dynamic { x: f32; }
+        block entry {
+            cache.read state[j] >> x;
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("unknown variable: j"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; }
+        block entry {
+            assign x: f32;
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("duplicate variable: x"));
+}
+
+#[test]
+fn rejects_temporaries_used_outside_their_scope() {
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B]; }
+        block entry {
+            op relu(t, alpha=0.1) >> x;
+            assign t: f32[B];
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("variable t is used before it is assigned"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B]; }
+        block entry {
+            assign t: f32[B];
+            op relu(x, alpha=0.1) >> t;
+            branch decode;
+        }
+        block decode {
+            transfer t >> x;
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("variable t is not assigned in block decode"));
+
+    validate_src(
+        r#"
+        dynamic { x: f32[B]; }
+        block entry {
+            loop l (i in 0..4) {
+                assign t: f32[B];
+                op relu(x, alpha=0.1) >> t;
+            }
+            transfer t >> x;
+            return;
+        }
+        "#,
+    )
+    .expect("assigns in loop bodies stay in scope for the rest of the block");
+}
+
+#[test]
+fn rejects_invalid_block_targets() {
+    validate_src(
+        r#"
+        dynamic { c: bool; }
+        block entry {
+            branch c left right;
+            dep after(left) before(right);
+        }
+        block left { branch right; }
+        block right { return; }
+        "#,
+    )
+    .expect("valid graph");
+
+    let err = validate_err(
+        r#"
+        dynamic { c: bool; }
+        block entry { branch c left missing; }
+        block left { return; }
+        "#,
+    );
+    assert!(err.contains("unknown block: missing"));
+
+    let err = validate_err(
+        r#"
+        block entry { return; }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("duplicate block: entry"));
+
+    let err = validate_err("block main { return; }");
+    assert!(err.contains("graph must declare an `entry` block"));
+}
+
+#[test]
+fn rejects_unknown_ops_with_suggestion() {
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; y: f32; z: f32; }
+        block entry { op ad(x, y) >> z; }
+        "#,
+    );
+    assert!(err.contains("unknown op: ad; did you mean `add`?"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op add(x) >> z; }
+        "#,
+    );
+    assert!(err.contains("op add expects 2 inputs, got 1"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op relu(x, clamp_mx=6.0) >> z; }
+        "#,
+    );
+    assert!(err.contains("unsupported setting for op relu: clamp_mx; did you mean `clamp_max`?"));
+}
+
+#[test]
+fn checks_op_settings_against_schema() {
+    let graph = parse_str::<GraphDsl>(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op relu(x, alpha=1) >> z; }
+        "#,
+    )
+    .expect("parse graph");
+    let op = match &graph.sections[1] {
+        crate::types::Section::Block(block) => match &block.nodes[0] {
+            crate::types::Node::Op(op) => op,
+            _ => panic!("expected op"),
+        },
+        _ => panic!("expected block"),
+    };
+    let spec = registry::lookup(&op.name).expect("known op");
+    let resolved = schema::resolve(spec, &op.name, &op.settings).expect("valid settings");
+    assert_eq!(resolved.len(), 2);
+    assert_eq!(resolved[0].name, "alpha");
+    assert!(matches!(resolved[0].value, OpAttrValue::Double(v) if v == 1.0));
+    assert!(matches!(resolved[1].value, OpAttrValue::Double(v) if v.is_infinite()));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op relu(x, alpha=true) >> z; }
+        "#,
+    );
+    assert!(err.contains("setting alpha of op relu expects a float"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op cast(x) >> z; }
+        "#,
+    );
+    assert!(err.contains("missing required setting for op cast: to"));
+
+    // Every bad setting is reported, not only the first.
+    let errors = validate_src_or_parse(
+        r#"
+        dynamic { x: f32; z: f32; }
+        block entry { op relu(x, alpha=true, clamp_max=false, beta=1, gama=2, beta=3) >> z; }
+        "#,
+    );
+    assert_eq!(
+        errors,
+        [
+            "duplicate setting: beta",
+            "setting alpha of op relu expects a float",
+            "setting clamp_max of op relu expects a float",
+            "unsupported setting for op relu: beta",
+            "unsupported setting for op relu: gama",
+        ]
+    );
+
+    // Dtype lists are checked element by element; the parser rejects
+    // unknown dtypes itself, so build the setting directly.
+    let ident = |name: &str| syn::Ident::new(name, proc_macro2::Span::call_site());
+    let acc = crate::types::OpSetting {
+        name: ident("acc"),
+        value: OpAttrValue::DTypeList(vec![ident("i32"), ident("f33"), ident("q1")]),
+    };
+    let spec = registry::lookup(&ident("add")).expect("known op");
+    let errors: Vec<String> = schema::resolve(spec, &ident("add"), &[acc])
+        .err()
+        .expect("unknown dtypes")
+        .into_iter()
+        .map(|err| err.to_string())
+        .collect();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].contains("f33"));
+    assert!(errors[1].contains("q1"));
+}
+
+#[test]
+fn infers_and_checks_op_shapes() {
+    validate_src(
+        r#"
+        dynamic { x: f32[B, D]; bias: f32[D]; w: f32[D, K]; }
+        volatile { h: f32[B, D]; y: f32[B, K]; s: f32[B]; c: f32[B, D*2]; }
+        block entry {
+            op add(x, bias) >> h;
+            op matmul(h, w) >> y;
+            op sum(h, axes=[-1]) >> s;
+            op concat(x, h, axis=1) >> c;
+            return;
+        }
+        "#,
+    )
+    .expect("valid graph");
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B, D]; y: f32[D, B]; z: f32[B, D]; }
+        block entry { op add(x, y) >> z; }
+        "#,
+    );
+    assert!(err.contains("cannot broadcast y [D, B] against [B, D]"));
+
+    let err = validate_err(
+        r#"
+        dynamic { a: f32[B, D]; b: f32[K, N]; c: f32[B, N]; }
+        block entry { op matmul(a, b) >> c; }
+        "#,
+    );
+    assert!(err.contains("inner dims D of a and K of b differ"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B*D]; z: f32[B, D]; }
+        block entry { op relu(x) >> z; }
+        "#,
+    );
+    assert!(err.contains("output z is declared [B, D] but inferred [B*D]"));
+
+    validate_src(
+        r#"
+        dynamic { x: f32[B, D]; z: f32[B, D]; }
+        block entry { op softmax(x) >> z; op softmax(x, axis=0) >> z; }
+        "#,
+    )
+    .expect("valid softmax axes");
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[B, D]; z: f32[B, D]; }
+        block entry { op softmax(x, axis=9) >> z; }
+        "#,
+    );
+    assert!(err.contains("axis 9 is out of range for op softmax on a rank 2 input"));
+}
+
+#[test]
+fn propagates_dtypes_through_ops_and_transfers() {
+    validate_src(
+        r#"
+        dynamic { a: i8[N]; b: i8[N]; x: f32[N]; }
+        volatile { acc: i32[N]; h: f16[N]; ok: bool[N]; }
+        block entry {
+            op add(a, b, acc=[i32]) >> acc;
+            op cast(x, to=f16) >> h;
+            op is_finite(x) >> ok;
+            return;
+        }
+        "#,
+    )
+    .expect("valid graph");
+
+    let err = validate_err(
+        r#"
+        dynamic { a: f32[N]; b: f16[N]; c: f32[N]; }
+        block entry { op add(a, b) >> c; }
+        "#,
+    );
+    assert!(err.contains("dtype mismatch for op add: b is f16 but earlier inputs are f32"));
+
+    let err = validate_err(
+        r#"
+        dynamic { a: i8[N]; b: i8[N]; c: i64[N]; }
+        block entry { op mul(a, b, acc=[i32]) >> c; }
+        "#,
+    );
+    assert!(err.contains("output c is i64 but the op produces i8 or i32"));
+
+    let err = validate_err(
+        r#"
+        dynamic { a: f32[N]; b: f16[N]; }
+        block entry { transfer a >> b; }
+        "#,
+    );
+    assert!(err.contains("crosses dtypes f32 -> f16"));
+}
+
+#[test]
+fn enforces_memory_kind_access() {
+    let err = validate_err(
+        r#"
+        dynamic { x: f32; }
+        constant { w: f32; }
+        block entry { op add(x, x) >> w; }
+        "#,
+    );
+    assert!(err.contains("cannot write to constant variable w"));
+
+    let err = validate_err(
+        r#"
+        volatile { t: f32; s: f32; }
+        block entry { cache.write t >> s; }
+        "#,
+    );
+    assert!(err.contains("cache.write requires a persistent variable, but s is volatile"));
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32 @init(1.0); }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("@init has no effect on dynamic variable x"));
+}
+
+#[test]
+fn checks_table_attributes_and_index_arity() {
+    let err = validate_err(
+        r#"
+        persistent { state(i, j): f32 @table @auto_dim(k); }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("@auto_dim index k is not declared in the table indices of state"));
+
+    let err = validate_err(
+        r#"
+        persistent { state: f32 @table; }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("@table variable state must declare indices"));
+
+    let err = validate_err(
+        r#"
+        persistent { state(i, j): f32 @table; }
+        volatile { t: f32; }
+        block entry { cache.read state[0, 1, 2] >> t; }
+        "#,
+    );
+    assert!(err.contains("too many indices for state: table declares 2 (i, j), got 3"));
+
+    let err = validate_err(
+        r#"
+        volatile { t: f32; u: f32; }
+        block entry { transfer t[0] >> u; }
+        "#,
+    );
+    assert!(err.contains("t is not a table and cannot be indexed"));
+}
+
+#[test]
+fn reports_every_error_in_one_pass() {
+    let err = validate_src(
+        r#"
+        dynamic { x: f32; }
+        constant { w: f32; }
+        block entry {
+            op add(x, y) >> w;
+            transfer x >> z;
+            op matmul(x) >> x;
+            branch nowhere;
+        }
+        "#,
+    )
+    .expect_err("expected validation errors");
+    let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+    let has = |prefix: &str| messages.iter().any(|message| message.starts_with(prefix));
+    assert!(has("unknown block: nowhere"));
+    assert!(has("unknown variable: y"));
+    assert!(has("unknown variable: z"));
+    assert!(has("cannot write to constant variable w"));
+    assert!(has("op matmul expects 2 inputs, got 1"));
+}
+
+#[test]
+fn suggests_close_variable_and_block_names() {
+    let err = validate_err(
+        r#"
+        dynamic { hidden: f32; out: f32; }
+        block entry { op relu(hiden) >> out; }
+        "#,
+    );
+    assert!(err.contains("unknown variable: hiden; did you mean `hidden`?"));
+
+    let err = validate_err(
+        r#"
+        block entry { branch decod; }
+        block decode { return; }
+        "#,
+    );
+    assert!(err.contains("unknown block: decod; did you mean `decode`? (declared blocks: decode, entry)"));
+}
+
+#[test]
+fn normalises_dim_expressions() {
+    let normalise = |src: &str| {
+        let dim = crate::parsers::dims::parse_dim_expr
+            .parse_str(src)
+            .expect("parse dim");
+        shapes::sym_dim(&dim).expect("normalise dim").to_string()
+    };
+    assert_eq!(normalise("D*B"), "B*D");
+    assert_eq!(normalise("2*B*D"), "2*B*D");
+    assert_eq!(normalise("B + B*2"), "3*B");
+    assert_eq!(normalise("S + 1"), "S+1");
+    assert_eq!(normalise("(S - 1) * 2"), "2*S-2");
+    assert_eq!(normalise("(64 + 7) / 8"), "8");
+    assert_eq!(normalise("ceil(65 / 8)"), "9");
+    assert_eq!(normalise("(2*B + 4) / 2"), "B+2");
+    assert_eq!(normalise("(N + 7) / 8"), "floor((N+7)/8)");
+    assert_eq!(normalise("ceil(N / 8)"), "ceil(N/8)");
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[N / 0]; }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("division by zero in dimension expression"));
+
+    let errors: Vec<String> = validate_src(
+        r#"
+        dynamic { y: f32[4294967296*4294967296]; x: f32[N * 4611686018427387904 * 2]; }
+        block entry { return; }
+        "#,
+    )
+    .expect_err("overflowing dims")
+    .into_iter()
+    .map(|err| err.to_string())
+    .collect();
+    assert_eq!(errors, ["dimension expression overflows i64"; 2]);
+
+    validate_src(
+        r#"
+        dynamic { x: f32[B, D*H]; y: f32[B, H*D]; }
+        volatile { z: f32[B, H*D]; }
+        block entry { op add(x, y) >> z; return; }
+        "#,
+    )
+    .expect("dims are compared in normal form");
+}
+
+#[test]
+fn checks_declared_dimensions() {
+    validate_src(
+        r#"
+        dims { B: 1..=64; D = 768; H = 12 where D % H == 0; }
+        dynamic { x: f32[B, D]; }
+        volatile { y: f32[B, D / H]; }
+        block entry { return; }
+        "#,
+    )
+    .expect("declared dims");
+
+    let err = validate_err(
+        r#"
+        dims { B: 1..=64; D = 768; }
+        dynamic { x: f32[B, S]; y: f32[Bb]; }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("undeclared dimension: S"));
+
+    let errors = validate_src(
+        r#"
+        dims { B: 1..=64; B; D = 768; H = 10 where D % H == 0; E: 8..8; Z = 0; }
+        dynamic { y: f32[Bb]; }
+        block entry { return; }
+        "#,
+    )
+    .expect_err("invalid dims");
+    let messages: Vec<String> = errors.into_iter().map(|err| err.to_string()).collect();
+    let has = |text: &str| messages.iter().any(|message| message == text);
+    assert!(has("duplicate dimension: B"));
+    assert!(has("dimension constraint does not hold: 768 is not divisible by 10"));
+    assert!(has("empty range for dimension E"));
+    assert!(has("dimension Z must be positive"));
+    assert!(has("undeclared dimension: Bb; did you mean `B`?"));
+
+    let err = validate_err(
+        r#"
+        dims { D = 4294967296; H = 8 where D * D % H == 0; }
+        block entry { return; }
+        "#,
+    );
+    assert_eq!(err, "dimension expression overflows i64");
+}
+
+#[test]
+fn checks_loop_range_direction() {
+    validate_src(
+        r#"
+        block entry {
+            loop up (i in 0..8 step 2) { return; }
+            loop down (i in 7..=0 step -1) { return; }
+            loop sym (i in 0..S-1) { return; }
+            loop folded (i in (4-1)*2..0 step -3) { return; }
+            return;
+        }
+        "#,
+    )
+    .expect("valid loop ranges");
+
+    let err = validate_err("block entry { loop l (i in 8..0) { return; } }");
+    assert!(err.contains(
+        "loop l runs from 8 down to 0; reversed ranges need a negative step, e.g. 8..0 step -1"
+    ));
+
+    let err = validate_err("block entry { loop l (i in 0..=8 step -2) { return; } }");
+    assert!(err.contains("loop l has step -2 but its range 0..=8 is ascending"));
+}
+
+#[test]
+fn validates_unrolled_loop_bodies() {
+    validate_src(
+        r#"
+        dynamic { x: f32[D]; }
+        persistent { state(i): f32[D] @table; }
+        block entry {
+            loop layers (i in 0..2) @unroll {
+                assign h{i}: f32[D];
+                cache.read state[i] >> h{i};
+                op add(x, h{i}) >> x;
+            }
+            return;
+        }
+        "#,
+    )
+    .expect("unrolled body resolves");
+
+    let err = validate_err(
+        r#"
+        dynamic { x: f32[D]; }
+        block entry {
+            loop layers (i in 0..2) @unroll { op add(x, h{i}) >> x; }
+            return;
+        }
+        "#,
+    );
+    assert!(err.contains("unknown variable: h0"));
+}
+
+#[test]
+fn validates_repeated_declarations() {
+    validate_src(
+        r#"
+        dynamic { x: f32[D]; }
+        constant { repeat i in 0..2 { w{i}: f32[D]; } }
+        block entry {
+            repeat i in 0..2 { op add(x, w{i}) >> x; }
+            return;
+        }
+        "#,
+    )
+    .expect("repeated names resolve");
+
+    let err = validate_err(
+        r#"
+        constant { repeat i in 0..2 { w{i}: f32; } w1: f32; }
+        block entry { return; }
+        "#,
+    );
+    assert!(err.contains("duplicate variable: w1"));
+}
+
+#[test]
+fn checks_call_signatures() {
+    validate_src(
+        r#"
+        fn double(x: f32[B, D]) -> f32[B, D] {
+            assign t: f32[B, D];
+            op add(x, x) >> t;
+            return t;
+        }
+        fn id(x: f32[N]) -> f32[N] { return x; }
+        dynamic { a: f32[S, 768]; v: f32[K]; }
+        volatile { y: f32[S, 768]; u: f32[K]; }
+        block entry {
+            call double(a) >> y;
+            call double(y) >> y2;
+            call id(v) >> u;
+            return;
+        }
+        "#,
+    )
+    .expect("inlined calls validate");
+
+    // Names shared with an op, a setting key or a block are only renamed as
+    // variables.
+    validate_src(
+        r#"
+        fn act(relu: f32[B, D], entry: f32[B, D]) -> f32[B, D] {
+            assign alpha: f32[B, D];
+            op relu(relu, alpha=0.5) >> alpha;
+            dep after(entry) before(other);
+            return alpha;
+        }
+        dynamic { x: f32[S, 768]; }
+        block entry {
+            call act(x, x) >> y;
+            return;
+        }
+        block other { return; }
+        "#,
+    )
+    .expect("inlined names do not collide with ops or settings");
+
+    let errors = validate_src_or_parse(
+        r#"
+        fn double(x: f32[B, D]) -> f32[B, D] {
+            assign t: f32[B, D];
+            op add(x, x) >> t;
+            return t;
+        }
+        fn sq(x: f32[D, D]) -> f32[D, D] { return x; }
+        dynamic { a: f16[S, 768]; b: f32[S, 768]; c: f32[S, 512]; }
+        volatile { y: f32[S, 512]; z: f32[S, 768]; }
+        block entry {
+            call double(a) >> z;
+            call double(b) >> y;
+            call sq(c) >> z;
+            call doubel(b) >> z;
+            call double(b, c) >> z;
+            return;
+        }
+        "#,
+    );
+    let has = |text: &str| errors.iter().any(|message| message == text);
+    assert!(has("argument a of call double is f16 but parameter x is f32"));
+    assert!(has("call double returns [S, 768] but y is declared [S, 512]"));
+    assert!(has("argument c of call sq has shape [S, 512] but parameter x expects [S, S]"));
+    assert!(has("unknown fn: doubel; did you mean `double`?"));
+    assert!(has("fn double expects 1 argument, got 2"));
+}
+
+fn validate_src_or_parse(src: &str) -> Vec<String> {
+    let err = match parse_str::<GraphDsl>(src) {
+        Ok(graph) => validate(&graph).expect_err("expected validation error"),
+        Err(err) => err,
+    };
+    err.into_iter().map(|err| err.to_string()).collect()
+}
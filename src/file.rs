@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::LitStr;
+
+use openinfer_dsl_build::CompileError;
+use openinfer_dsl_syntax::json::from_json;
+
+/// Expand `graph_file!("path")`: read the file relative to
+/// `CARGO_MANIFEST_DIR`, report errors as `path:line:column` and make the
+/// crate rebuild when the file changes.
+pub(crate) fn expand_file(path: &LitStr) -> syn::Result<TokenStream> {
+    let (full_path, source) = read(path)?;
+
+    // Compiler spans carry no line/column on stable, so lex the file with
+    // proc-macro2's own lexer and run parsing, validation and code generation
+    // on those tokens; every error then maps to `path:line:column`.
+    let expanded = {
+        let _fallback = Fallback::force();
+        openinfer_dsl_build::compile_source(&path.value(), &source)
+            .map(|expanded| tracked(&full_path, expanded))
+    };
+    expanded.map_err(|err| {
+        let messages = match err {
+            CompileError::Invalid(messages) => messages,
+            err => vec![err.to_string()],
+        };
+        let mut errors = messages
+            .into_iter()
+            .map(|message| syn::Error::new(path.span(), message));
+        let mut error = errors.next().expect("at least one error");
+        error.extend(errors);
+        error
+    })
+}
+
+/// Keeps proc-macro2 on its own lexer until dropped. The switch is global to
+/// the compiler process, so it is undone even if expansion panics.
+struct Fallback;
+
+impl Fallback {
+    fn force() -> Self {
+        proc_macro2::fallback::force();
+        Fallback
+    }
+}
+
+impl Drop for Fallback {
+    fn drop(&mut self) {
+        proc_macro2::fallback::unforce();
+    }
+}
+
+/// Expand `graph_from_json!("path")`: read a graph written in the
+/// `openinfer_dsl_syntax::json` schema and expand it like `graph!`, with the
+/// same validation. Errors are reported at the macro call, prefixed with the
+/// path (and `line:column` for malformed JSON).
+pub(crate) fn expand_json_file(path: &LitStr) -> syn::Result<TokenStream> {
+    let (full_path, source) = read(path)?;
+    let graph = from_json(&source)
+        .map_err(|err| syn::Error::new(path.span(), format!("{}:{}", path.value(), err)))?;
+    let expanded = openinfer_dsl_build::expand(graph).map_err(|err| {
+        let mut errors = err
+            .into_iter()
+            .map(|err| syn::Error::new(path.span(), format!("{}: {}", path.value(), err)));
+        let mut error = errors.next().expect("at least one error");
+        error.extend(errors);
+        error
+    })?;
+    Ok(tracked(&full_path, expanded))
+}
+
+/// Read `path` relative to `CARGO_MANIFEST_DIR`.
+fn read(path: &LitStr) -> syn::Result<(PathBuf, String)> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+    let source = std::fs::read_to_string(&full_path).map_err(|err| {
+        syn::Error::new(
+            path.span(),
+            format!("cannot read {}: {}", full_path.display(), err),
+        )
+    })?;
+    Ok((full_path, source))
+}
+
+/// Wrap `expanded` so the crate rebuilds when `full_path` changes.
+fn tracked(full_path: &Path, expanded: TokenStream) -> TokenStream {
+    let tracked = full_path.to_string_lossy().into_owned();
+    quote! {{
+        const _: &[u8] = include_bytes!(#tracked);
+        #expanded
+    }}
+}
@@ -0,0 +1,49 @@
+use proc_macro2::Span;
+use syn::LitStr;
+
+use crate::file::expand_file;
+
+fn write_scratch(name: &str, source: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("openinfer-dsl-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create scratch dir");
+    let path = dir.join(name);
+    std::fs::write(&path, source).expect("write dsl file");
+    path.display().to_string()
+}
+
+#[test]
+fn expands_dsl_files_and_tracks_them() {
+    let path = write_scratch(
+        "ok.oinf",
+        "dynamic { x: f32[B]; }\nblock entry {\n    op relu(x) >> x;\n    return;\n}\n",
+    );
+    let expanded = expand_file(&LitStr::new(&path, Span::call_site()))
+        .expect("expand dsl file")
+        .to_string();
+    assert!(expanded.contains(&format!("include_bytes ! ({:?})", path)));
+    assert!(expanded.contains("g . add_block (\"entry\")"));
+}
+
+#[test]
+fn reports_validation_and_codegen_errors_with_line_and_column() {
+    let path = write_scratch(
+        "bad.oinf",
+        "dynamic { x: f32[B]; }\nblock entry {\n    op ad(x) >> x;\n    return;\n}\n",
+    );
+    let err = expand_file(&LitStr::new(&path, Span::call_site())).expect_err("unknown op");
+    assert_eq!(
+        err.to_string(),
+        format!("{}:3:8: unknown op: ad; did you mean `add`?", path)
+    );
+
+    // Raised by code generation, after validation passed.
+    let path = write_scratch(
+        "init.oinf",
+        "volatile {\n    n: i8 @init(300);\n}\nblock entry {\n    return;\n}\n",
+    );
+    let err = expand_file(&LitStr::new(&path, Span::call_site())).expect_err("out of range");
+    assert_eq!(
+        err.to_string(),
+        format!("{}:2:8: i8 init out of range", path)
+    );
+}
@@ -4,12 +4,35 @@
 //! It is intended for ergonomics in tests and examples.
 //!
 //! ## DSL structure
+//! - Symbolic dimensions: `dims { B: 1..=64; D = 768; H where D % H == 0; }`
 //! - Memory sections: `dynamic`, `volatile`, `constant`, `persistent`
 //! - Blocks: `block entry { ... }`
+//! - Subgraphs: `fn mlp(x: f32[B, D]) -> f32[B, D] { ...; return y; }`, inlined
+//!   at each `call mlp(x) >> y;`
 //! - Nodes: `assign`, `op`, `branch`, `loop`, `yield`, `await`, cache ops
+//! - Compile-time repetition: `repeat i in 0..24 { w{i}: f32[D] @ref("layers.{i}.w"); }`
+//!   in memory sections and blocks, and `loop l (i in 0..4) @unroll { ... }`
+//!
+//! ## Files
+//! `graph_file!("models/llama.oinf")` reads the same DSL from a file relative
+//! to `CARGO_MANIFEST_DIR`; errors point at `path:line:column`.
+//!
+//! ## Diagrams
+//! `graph_dot! { ... }` takes the same DSL and expands to a `&'static str`
+//! holding a Graphviz DOT rendering of the graph.
+//!
+//! ## JSON
+//! `graph_json! { ... }` expands to a `&'static str` holding the validated
+//! graph as versioned JSON (see `openinfer_dsl_syntax::json` for the schema),
+//! for tools that do not link Rust. `graph_from_json!("graph.json")` goes the
+//! other way: it reads such a file relative to `CARGO_MANIFEST_DIR` and
+//! expands to the same code as `graph!`, after the same validation.
 //!
 //! ## Expansion
 //! The macro expands into Rust code that constructs `Graph` values at runtime.
+//! Parsing and validation live in the `openinfer-dsl-syntax` crate and code
+//! generation in `openinfer-dsl-build`, which also compiles DSL files from
+//! build scripts.
 //!
 //! ## Example
 //! ```ignore
@@ -23,50 +46,72 @@
 //! ```
 use proc_macro::TokenStream;
 
-mod attributes;
-mod codegen;
-mod parsers;
-mod types;
-mod validation;
+mod file;
 
-mod kw {
-    syn::custom_keyword!(dynamic);
-    syn::custom_keyword!(volatile);
-    syn::custom_keyword!(constant);
-    syn::custom_keyword!(persistent);
-    syn::custom_keyword!(block);
-    syn::custom_keyword!(assign);
-    syn::custom_keyword!(op);
-    syn::custom_keyword!(branch);
-    syn::custom_keyword!(barrier);
-    syn::custom_keyword!(dep);
-    syn::custom_keyword!(after);
-    syn::custom_keyword!(before);
-    syn::custom_keyword!(transfer);
-    syn::custom_keyword!(cache);
-    syn::custom_keyword!(read);
-    syn::custom_keyword!(write);
-    syn::custom_keyword!(increment);
-    syn::custom_keyword!(decrement);
-    syn::custom_keyword!(reset);
-    syn::custom_keyword!(init);
-    syn::custom_keyword!(pattern);
-    syn::custom_keyword!(table);
-    syn::custom_keyword!(fixed);
-    syn::custom_keyword!(auto_dim);
-}
-
-use crate::types::GraphDsl;
+use openinfer_dsl_syntax::GraphDsl;
 
 /// Build an OpenInfer `Graph` from the DSL input.
 #[proc_macro]
 pub fn graph(input: TokenStream) -> TokenStream {
     let ast = syn::parse_macro_input!(input as GraphDsl);
-    match ast.expand() {
-        Ok(ts) => ts,
+    match openinfer_dsl_build::expand(ast) {
+        Ok(ts) => ts.into(),
         Err(err) => err.to_compile_error().into(),
     }
 }
 
+/// Build an OpenInfer `Graph` from a DSL file, e.g.
+/// `graph_file!("models/llama.oinf")`. The path is relative to the crate's
+/// `CARGO_MANIFEST_DIR`; editing the file triggers a rebuild.
+#[proc_macro]
+pub fn graph_file(input: TokenStream) -> TokenStream {
+    let path = syn::parse_macro_input!(input as syn::LitStr);
+    match file::expand_file(&path) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Build an OpenInfer `Graph` from a JSON graph description, e.g.
+/// `graph_from_json!("models/llama.json")`, in the schema written by
+/// `graph_json!`. The path is relative to the crate's `CARGO_MANIFEST_DIR`;
+/// the graph is validated and expanded like in `graph!`.
+#[proc_macro]
+pub fn graph_from_json(input: TokenStream) -> TokenStream {
+    let path = syn::parse_macro_input!(input as syn::LitStr);
+    match file::expand_json_file(&path) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Render the DSL input as Graphviz DOT, e.g.
+/// `std::fs::write("graph.dot", graph_dot! { ... })`. Blocks become
+/// clusters, loops nested clusters and variables are coloured by memory
+/// kind. The graph is validated like in `graph!`.
+#[proc_macro]
+pub fn graph_dot(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as GraphDsl);
+    if let Err(err) = openinfer_dsl_syntax::validation::validate(&ast) {
+        return err.to_compile_error().into();
+    }
+    let dot = openinfer_dsl_syntax::dot::to_dot(&ast);
+    quote::quote!(#dot).into()
+}
+
+/// Render the DSL input as versioned JSON, e.g.
+/// `std::fs::write("graph.json", graph_json! { ... })`. The schema is
+/// documented in `openinfer_dsl_syntax::json`; calls and `@unroll` loops are
+/// written expanded. The graph is validated like in `graph!`.
+#[proc_macro]
+pub fn graph_json(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as GraphDsl);
+    if let Err(err) = openinfer_dsl_syntax::validation::validate(&ast) {
+        return err.to_compile_error().into();
+    }
+    let json = openinfer_dsl_syntax::json::to_json(&ast);
+    quote::quote!(#json).into()
+}
+
 #[cfg(test)]
-mod parse_tests;
+mod file_tests;